@@ -8,5 +8,33 @@ namespace MelonLoader.NativeHost
         internal delegate* unmanaged<string, void> LogConsole;
         internal delegate* unmanaged<void**> GetJavaVM;
         internal delegate* unmanaged<string> GetPackageName;
+        internal delegate* unmanaged<byte> GetLoaderPhase;
+        internal delegate* unmanaged<byte> GetFailedPhase;
+
+        internal delegate* unmanaged<uint> HookCount;
+        internal delegate* unmanaged<uint, void*, byte> HookGet;
+        internal delegate* unmanaged<uint, byte> HookDetachId;
+        internal delegate* unmanaged<void> HookDetachAll;
+        internal delegate* unmanaged<string, string, void*, void**, uint> HookAttachPlt;
+        internal delegate* unmanaged<void**, void*, int> HookAttachEx;
+        internal delegate* unmanaged<void**, void*, int> HookDetachEx;
+        internal delegate* unmanaged<byte*> HookGetLastError;
+
+        internal delegate* unmanaged<uint> TransactionBegin;
+        internal delegate* unmanaged<uint, void**, void*, byte> TransactionAttach;
+        internal delegate* unmanaged<uint, void**, void*, byte> TransactionDetach;
+        internal delegate* unmanaged<uint, void*, uint, byte> TransactionCommit;
+        internal delegate* unmanaged<uint, byte> TransactionRollback;
+
+        internal delegate* unmanaged<string, string, nint*, uint, uint> SigScan;
+
+        internal delegate* unmanaged<uint> ModuleCount;
+        internal delegate* unmanaged<uint, void*, byte> ModuleGet;
+        internal delegate* unmanaged<nuint, uint*, nuint*, byte> ModuleResolve;
+        internal delegate* unmanaged<string, string, void*> SymbolResolve;
+
+        internal delegate* unmanaged<void*, byte*, nuint, uint> PatchWrite;
+        internal delegate* unmanaged<void*, nuint, uint> PatchNop;
+        internal delegate* unmanaged<void*, void*, uint> PatchWritePointer;
     }
 }
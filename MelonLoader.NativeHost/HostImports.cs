@@ -7,5 +7,6 @@
         public delegate* unmanaged[Stdcall]<void> Initialize;
         public delegate* unmanaged[Stdcall]<void> PreStart;
         public delegate* unmanaged[Stdcall]<void> Start;
+        public delegate* unmanaged[Stdcall]<void> Shutdown;
     }
 }
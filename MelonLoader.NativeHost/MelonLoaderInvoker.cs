@@ -6,14 +6,38 @@
         {
             BootstrapInterop.HookAttach = NativeEntryPoint.Exports.HookAttach;
             BootstrapInterop.HookDetach = NativeEntryPoint.Exports.HookDetach;
+            BootstrapInterop.HookAttachEx = NativeEntryPoint.Exports.HookAttachEx;
+            BootstrapInterop.HookDetachEx = NativeEntryPoint.Exports.HookDetachEx;
+            BootstrapInterop.HookGetLastError = NativeEntryPoint.Exports.HookGetLastError;
+            BootstrapInterop.HookCount = NativeEntryPoint.Exports.HookCount;
+            BootstrapInterop.HookGet = NativeEntryPoint.Exports.HookGet;
+            BootstrapInterop.HookDetachId = NativeEntryPoint.Exports.HookDetachId;
+            BootstrapInterop.HookDetachAll = NativeEntryPoint.Exports.HookDetachAll;
+            BootstrapInterop.HookAttachPlt = NativeEntryPoint.Exports.HookAttachPlt;
+            BootstrapInterop.TransactionBegin = NativeEntryPoint.Exports.TransactionBegin;
+            BootstrapInterop.TransactionAttach = NativeEntryPoint.Exports.TransactionAttach;
+            BootstrapInterop.TransactionDetach = NativeEntryPoint.Exports.TransactionDetach;
+            BootstrapInterop.TransactionCommit = NativeEntryPoint.Exports.TransactionCommit;
+            BootstrapInterop.TransactionRollback = NativeEntryPoint.Exports.TransactionRollback;
+            BootstrapInterop.SigScan = NativeEntryPoint.Exports.SigScan;
+            BootstrapInterop.ModuleCount = NativeEntryPoint.Exports.ModuleCount;
+            BootstrapInterop.ModuleGet = NativeEntryPoint.Exports.ModuleGet;
+            BootstrapInterop.ModuleResolve = NativeEntryPoint.Exports.ModuleResolve;
+            BootstrapInterop.SymbolResolve = NativeEntryPoint.Exports.SymbolResolve;
+            BootstrapInterop.PatchWrite = NativeEntryPoint.Exports.PatchWrite;
+            BootstrapInterop.PatchNop = NativeEntryPoint.Exports.PatchNop;
+            BootstrapInterop.PatchWritePointer = NativeEntryPoint.Exports.PatchWritePointer;
             BootstrapInterop.LogConsole = NativeEntryPoint.Exports.LogConsole;
             BootstrapInterop.GetJavaVM = NativeEntryPoint.Exports.GetJavaVM;
             BootstrapInterop.GetPackageName = NativeEntryPoint.Exports.GetPackageName;
+            BootstrapInterop.GetLoaderPhase = NativeEntryPoint.Exports.GetLoaderPhase;
+            BootstrapInterop.GetFailedPhase = NativeEntryPoint.Exports.GetFailedPhase;
 
             Core.Initialize();
         }
 
         internal static void PreStart() => Core.PreStart();
         internal static void Start() => Core.Start();
+        internal static void Shutdown() => Core.Quit();
     }
 }
@@ -25,6 +25,7 @@ namespace MelonLoader.NativeHost
             imports->Initialize = &Initialize;
             imports->PreStart = &PreStart;
             imports->Start = &Start;
+            imports->Shutdown = &Shutdown;
 
             Exports = *exports;
         }
@@ -82,6 +83,22 @@ namespace MelonLoader.NativeHost
             }
         }
 
+        [UnmanagedCallersOnly(CallConvs = new[] { typeof(CallConvStdcall) })]
+        static void Shutdown()
+        {
+            Console.WriteLine("[NewEntryPoint] Shutting down.");
+
+            //the host exits right after this returns, so there's nothing left to do on failure besides reporting it
+            try
+            {
+                MelonLoaderInvoker.Shutdown();
+            }
+            catch (Exception ex)
+            {
+                WriteLine("[NewEntryPoint] Caught exception invoking Shutdown! " + ex);
+            }
+        }
+
         private static Assembly? OnResolveAssembly(AssemblyLoadContext alc, AssemblyName name)
         {
             var ourDir = Path.GetDirectoryName(Assembly.GetExecutingAssembly().Location)!;
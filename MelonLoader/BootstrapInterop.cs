@@ -8,14 +8,129 @@ using MelonLoader.CoreClrUtils;
 
 namespace MelonLoader
 {
+    public enum NativeHookStatus
+    {
+        Ok = 0,
+        Null = 1,
+        Nullpointer = 2,
+        Failed = 3,
+        Dobby = 4,
+        NotHooked = 5,
+        NotExecutable = 6
+    }
+
+    public enum NativeLoaderPhase : byte
+    {
+        NotStarted = 0,
+        ConsoleInit = 1,
+        InitHooked = 2,
+        RuntimeInitialized = 3,
+        HostLoaded = 4,
+        PreStarted = 5,
+        Started = 6,
+        Failed = 7
+    }
+
+    public enum NativeHookState : byte
+    {
+        Attached = 0,
+        Detached = 1
+    }
+
+    public enum NativeHookKind : byte
+    {
+        Inline = 0,
+        Chained = 1,
+        Patch = 2,
+        Plt = 3
+    }
+
+    [StructLayout(LayoutKind.Sequential)]
+    public struct NativeHookInfo
+    {
+        public uint Id;
+        public NativeHookState State;
+        public NativeHookKind Kind;
+        public IntPtr Target;
+        public IntPtr Detour;
+        public IntPtr Trampoline;
+        public long AttachedAt;
+        private IntPtr _name;
+        private IntPtr _owner;
+
+        public string Name => Marshal.PtrToStringAnsi(_name);
+        public string Owner => Marshal.PtrToStringAnsi(_owner);
+    }
+
+    public enum NativeTransactionStatus : byte
+    {
+        Applied = 0,
+        Failed = 1,
+        RolledBack = 2,
+        Skipped = 3
+    }
+
+    [StructLayout(LayoutKind.Sequential)]
+    public struct NativeTransactionResult
+    {
+        public IntPtr Target;
+        public NativeTransactionStatus Status;
+        public uint HookId;
+    }
+
+    [Flags]
+    public enum NativeModulePermissions : byte
+    {
+        None = 0,
+        Read = 1,
+        Write = 2,
+        Execute = 4
+    }
+
+    [StructLayout(LayoutKind.Sequential)]
+    public struct NativeModuleInfo
+    {
+        public IntPtr Base;
+        public UIntPtr Size;
+        public NativeModulePermissions Permissions;
+        private IntPtr _name;
+        private IntPtr _path;
+
+        public string Name => Marshal.PtrToStringAnsi(_name);
+        public string Path => Marshal.PtrToStringAnsi(_path);
+    }
+
     internal static unsafe class BootstrapInterop
     {
 #if NET6_0_OR_GREATER
         internal static delegate* unmanaged<void**, void*, void> HookAttach;
         internal static delegate* unmanaged<void**, void*, void> HookDetach;
+        internal static delegate* unmanaged<void**, void*, int> HookAttachEx;
+        internal static delegate* unmanaged<void**, void*, int> HookDetachEx;
+        internal static delegate* unmanaged<byte*> HookGetLastError;
+        internal static delegate* unmanaged<uint> HookCount;
+        internal static delegate* unmanaged<uint, void*, byte> HookGet;
+        internal static delegate* unmanaged<uint, byte> HookDetachId;
+        internal static delegate* unmanaged<void> HookDetachAll;
+        internal static delegate* unmanaged<string, string, void*, void**, uint> HookAttachPlt;
+        internal static delegate* unmanaged<uint> TransactionBegin;
+        internal static delegate* unmanaged<uint, void**, void*, byte> TransactionAttach;
+        internal static delegate* unmanaged<uint, void**, void*, byte> TransactionDetach;
+        internal static delegate* unmanaged<uint, void*, uint, byte> TransactionCommit;
+        internal static delegate* unmanaged<uint, byte> TransactionRollback;
+        internal static delegate* unmanaged<string, string, nint*, uint, uint> SigScan;
+        internal static delegate* unmanaged<uint> ModuleCount;
+        internal static delegate* unmanaged<uint, void*, byte> ModuleGet;
+        internal static delegate* unmanaged<nuint, uint*, nuint*, byte> ModuleResolve;
+        internal static delegate* unmanaged<string, string, void*> SymbolResolve;
+        internal static delegate* unmanaged<void*, byte*, nuint, uint> PatchWrite;
+        internal static delegate* unmanaged<void*, nuint, uint> PatchNop;
+        internal static delegate* unmanaged<void*, void*, uint> PatchWritePointer;
         internal static delegate* unmanaged<string, void> LogConsole;
         internal static delegate* unmanaged<void**> GetJavaVM;
         internal static delegate* unmanaged<string> GetPackageName;
+        internal static delegate* unmanaged<byte> GetLoaderPhase;
+        internal static delegate* unmanaged<byte> GetFailedPhase;
 #endif
 
         internal static void SetDefaultConsoleTitleWithGameName([MarshalAs(UnmanagedType.LPStr)] string GameName, [MarshalAs(UnmanagedType.LPStr)] string GameVersion = null)
@@ -63,11 +178,57 @@ namespace MelonLoader
         [MethodImpl(MethodImplOptions.InternalCall)]
         public static extern void NativeHookDetach(IntPtr target, IntPtr detour);
         [MethodImpl(MethodImplOptions.InternalCall)]
+        public static extern NativeHookStatus NativeHookAttachEx(IntPtr target, IntPtr detour);
+        [MethodImpl(MethodImplOptions.InternalCall)]
+        public static extern NativeHookStatus NativeHookDetachEx(IntPtr target, IntPtr detour);
+        [MethodImpl(MethodImplOptions.InternalCall)]
+        private static extern IntPtr NativeHookGetLastErrorPtr();
+        [MethodImpl(MethodImplOptions.InternalCall)]
+        public static extern uint NativeHookCount();
+        [MethodImpl(MethodImplOptions.InternalCall)]
+        public static extern bool NativeHookGet(uint index, out NativeHookInfo info);
+        [MethodImpl(MethodImplOptions.InternalCall)]
+        public static extern bool NativeHookDetachId(uint id);
+        [MethodImpl(MethodImplOptions.InternalCall)]
+        public static extern void NativeHookDetachAll();
+        [MethodImpl(MethodImplOptions.InternalCall)]
+        private static extern uint NativeHookAttachPlt(IntPtr module, IntPtr symbol, IntPtr detour, IntPtr* original);
+        [MethodImpl(MethodImplOptions.InternalCall)]
+        public static extern uint NativeTransactionBegin();
+        [MethodImpl(MethodImplOptions.InternalCall)]
+        public static extern bool NativeTransactionAttach(uint id, IntPtr target, IntPtr detour);
+        [MethodImpl(MethodImplOptions.InternalCall)]
+        public static extern bool NativeTransactionDetach(uint id, IntPtr target, IntPtr detour);
+        [MethodImpl(MethodImplOptions.InternalCall)]
+        private static extern bool NativeTransactionCommit(uint id, NativeTransactionResult* results, uint max);
+        [MethodImpl(MethodImplOptions.InternalCall)]
+        public static extern bool NativeTransactionRollback(uint id);
+        [MethodImpl(MethodImplOptions.InternalCall)]
+        private static extern uint NativeSigScan(IntPtr module, IntPtr pattern, IntPtr* matches, uint max);
+        [MethodImpl(MethodImplOptions.InternalCall)]
+        public static extern uint NativeModuleCount();
+        [MethodImpl(MethodImplOptions.InternalCall)]
+        public static extern bool NativeModuleGet(uint index, out NativeModuleInfo info);
+        [MethodImpl(MethodImplOptions.InternalCall)]
+        public static extern bool NativeModuleResolve(IntPtr address, out uint index, out UIntPtr offset);
+        [MethodImpl(MethodImplOptions.InternalCall)]
+        private static extern IntPtr NativeSymbolResolve(IntPtr module, IntPtr symbol);
+        [MethodImpl(MethodImplOptions.InternalCall)]
+        private static extern uint NativePatchWrite(IntPtr address, byte* bytes, UIntPtr length);
+        [MethodImpl(MethodImplOptions.InternalCall)]
+        public static extern uint NativePatchNop(IntPtr address, UIntPtr length);
+        [MethodImpl(MethodImplOptions.InternalCall)]
+        public static extern uint NativePatchWritePointer(IntPtr address, IntPtr value);
+        [MethodImpl(MethodImplOptions.InternalCall)]
         public static extern void NativeLogConsole(string msg);
         [MethodImpl(MethodImplOptions.InternalCall)]
         public static extern IntPtr NativeGetJavaVM();
         [MethodImpl(MethodImplOptions.InternalCall)]
         public static extern string NativeGetPackageName();
+        [MethodImpl(MethodImplOptions.InternalCall)]
+        public static extern NativeLoaderPhase NativeGetLoaderPhase();
+        [MethodImpl(MethodImplOptions.InternalCall)]
+        public static extern NativeLoaderPhase NativeGetFailedPhase();
 #else
         public static void NativeHookAttach(IntPtr target, IntPtr detour)
         {
@@ -90,6 +251,108 @@ namespace MelonLoader
             NativeStackWalk.UnregisterHookAddr((ulong)target);
 
         }
+
+        public static unsafe NativeHookStatus NativeHookAttachEx(IntPtr target, IntPtr detour)
+        {
+            return (NativeHookStatus)HookAttachEx((void**)target, (void*)detour);
+        }
+
+        public static unsafe NativeHookStatus NativeHookDetachEx(IntPtr target, IntPtr detour)
+        {
+            return (NativeHookStatus)HookDetachEx((void**)target, (void*)detour);
+        }
+
+        private static unsafe IntPtr NativeHookGetLastErrorPtr()
+        {
+            return (IntPtr)HookGetLastError();
+        }
+
+        public static unsafe uint NativeHookCount()
+        {
+            return HookCount();
+        }
+
+        public static unsafe bool NativeHookGet(uint index, out NativeHookInfo info)
+        {
+            NativeHookInfo result;
+            bool found = HookGet(index, &result) != 0;
+            info = found ? result : default;
+            return found;
+        }
+
+        public static unsafe bool NativeHookDetachId(uint id)
+        {
+            return HookDetachId(id) != 0;
+        }
+
+        public static unsafe void NativeHookDetachAll()
+        {
+            HookDetachAll();
+        }
+
+        public static unsafe uint NativeTransactionBegin()
+        {
+            return TransactionBegin();
+        }
+
+        public static unsafe bool NativeTransactionAttach(uint id, IntPtr target, IntPtr detour)
+        {
+            return TransactionAttach(id, (void**)target, (void*)detour) != 0;
+        }
+
+        public static unsafe bool NativeTransactionDetach(uint id, IntPtr target, IntPtr detour)
+        {
+            return TransactionDetach(id, (void**)target, (void*)detour) != 0;
+        }
+
+        private static unsafe bool NativeTransactionCommit(uint id, NativeTransactionResult* results, uint max)
+        {
+            return TransactionCommit(id, results, max) != 0;
+        }
+
+        public static unsafe bool NativeTransactionRollback(uint id)
+        {
+            return TransactionRollback(id) != 0;
+        }
+
+        public static unsafe uint NativeModuleCount()
+        {
+            return ModuleCount();
+        }
+
+        public static unsafe bool NativeModuleGet(uint index, out NativeModuleInfo info)
+        {
+            NativeModuleInfo result;
+            bool found = ModuleGet(index, &result) != 0;
+            info = found ? result : default;
+            return found;
+        }
+
+        public static unsafe bool NativeModuleResolve(IntPtr address, out uint index, out UIntPtr offset)
+        {
+            uint resultIndex = 0;
+            nuint resultOffset = 0;
+            bool found = ModuleResolve((nuint)(nint)address, &resultIndex, &resultOffset) != 0;
+            index = resultIndex;
+            offset = (UIntPtr)resultOffset;
+            return found;
+        }
+
+        private static unsafe uint NativePatchWrite(IntPtr address, byte* bytes, UIntPtr length)
+        {
+            return PatchWrite((void*)address, bytes, (nuint)length);
+        }
+
+        public static unsafe uint NativePatchNop(IntPtr address, UIntPtr length)
+        {
+            return PatchNop((void*)address, (nuint)length);
+        }
+
+        public static unsafe uint NativePatchWritePointer(IntPtr address, IntPtr value)
+        {
+            return PatchWritePointer((void*)address, (void*)value);
+        }
+
         public static unsafe void NativeLogConsole(string msg)
         {
             LogConsole(msg);
@@ -104,6 +367,143 @@ namespace MelonLoader
         {
             return GetPackageName();
         }
+
+        public static unsafe NativeLoaderPhase NativeGetLoaderPhase()
+        {
+            return (NativeLoaderPhase)GetLoaderPhase();
+        }
+
+        public static unsafe NativeLoaderPhase NativeGetFailedPhase()
+        {
+            return (NativeLoaderPhase)GetFailedPhase();
+        }
+#endif
+
+        /// <summary>
+        /// The message of the last failed <see cref="NativeHookAttachEx"/> or <see cref="NativeHookDetachEx"/> on this thread, or null.
+        /// </summary>
+        public static string NativeHookGetLastError()
+        {
+            // the native side owns the string, so it's copied rather than marshalled and freed
+            return Marshal.PtrToStringAnsi(NativeHookGetLastErrorPtr());
+        }
+
+        /// <summary>
+        /// Redirects the calls <paramref name="module"/> makes to its import <paramref name="symbol"/> to <paramref name="detour"/>,
+        /// by rewriting the module's GOT slots. Calls made from other modules are untouched.
+        /// Returns the hook id, or 0 on failure. <paramref name="original"/> receives the function the import pointed at.
+        /// </summary>
+        public static unsafe uint NativeHookAttachPlt(string module, string symbol, IntPtr detour, out IntPtr original)
+        {
+            IntPtr result = IntPtr.Zero;
+#if NET6_0_OR_GREATER
+            uint id = HookAttachPlt(module, symbol, (void*)detour, (void**)&result);
+#else
+            IntPtr modulePtr = Marshal.StringToHGlobalAnsi(module);
+            IntPtr symbolPtr = Marshal.StringToHGlobalAnsi(symbol);
+            uint id;
+            try
+            {
+                id = NativeHookAttachPlt(modulePtr, symbolPtr, detour, &result);
+            }
+            finally
+            {
+                Marshal.FreeHGlobal(modulePtr);
+                Marshal.FreeHGlobal(symbolPtr);
+            }
+#endif
+            original = result;
+            return id;
+        }
+
+        /// <summary>
+        /// Applies every queued operation of a transaction, or none of them.
+        /// <paramref name="results"/> receives one entry per operation, in the order they were queued.
+        /// </summary>
+        public static unsafe bool NativeTransactionCommit(uint id, NativeTransactionResult[] results)
+        {
+            if (results == null || results.Length == 0)
+                return NativeTransactionCommit(id, null, 0);
+
+            fixed (NativeTransactionResult* resultsPtr = results)
+                return NativeTransactionCommit(id, resultsPtr, (uint)results.Length);
+        }
+
+        /// <summary>
+        /// Overwrites the code at <paramref name="address"/> with <paramref name="bytes"/>, keeping the original bytes.
+        /// Returns the patch's hook id, which <see cref="NativeHookDetachId"/> undoes, or 0 on failure.
+        /// </summary>
+        public static unsafe uint NativePatchWrite(IntPtr address, byte[] bytes)
+        {
+            if (bytes == null || bytes.Length == 0)
+                return 0;
+
+            fixed (byte* bytesPtr = bytes)
+                return NativePatchWrite(address, bytesPtr, (UIntPtr)bytes.Length);
+        }
+
+        /// <summary>
+        /// Resolves a symbol of a loaded module, including ones only found in its symbol table or a sidecar file.
+        /// Returns <see cref="IntPtr.Zero"/> if it can't be found.
+        /// </summary>
+        public static unsafe IntPtr NativeSymbolResolve(string module, string symbol)
+        {
+#if NET6_0_OR_GREATER
+            return (IntPtr)SymbolResolve(module, symbol);
+#else
+            IntPtr modulePtr = Marshal.StringToHGlobalAnsi(module);
+            IntPtr symbolPtr = Marshal.StringToHGlobalAnsi(symbol);
+            try
+            {
+                return NativeSymbolResolve(modulePtr, symbolPtr);
+            }
+            finally
+            {
+                Marshal.FreeHGlobal(modulePtr);
+                Marshal.FreeHGlobal(symbolPtr);
+            }
+#endif
+        }
+
+        /// <summary>
+        /// Scans a loaded module for an IDA-style signature, e.g. "48 8B ?? ?? 89".
+        /// </summary>
+        public static unsafe IntPtr[] NativeSigScan(string module, string pattern)
+        {
+            IntPtr[] matches = new IntPtr[16];
+            uint count = SigScanInto(module, pattern, matches);
+
+            // the first scan reports the total, so at most one more is needed to fit everything
+            if (count > matches.Length)
+            {
+                matches = new IntPtr[count];
+                count = SigScanInto(module, pattern, matches);
+            }
+
+            Array.Resize(ref matches, (int)Math.Min(count, (uint)matches.Length));
+            return matches;
+        }
+
+        private static unsafe uint SigScanInto(string module, string pattern, IntPtr[] matches)
+        {
+            fixed (IntPtr* matchesPtr = matches)
+            {
+#if NET6_0_OR_GREATER
+                return SigScan(module, pattern, (nint*)matchesPtr, (uint)matches.Length);
+#else
+                IntPtr modulePtr = Marshal.StringToHGlobalAnsi(module);
+                IntPtr patternPtr = Marshal.StringToHGlobalAnsi(pattern);
+                try
+                {
+                    return NativeSigScan(modulePtr, patternPtr, matchesPtr, (uint)matches.Length);
+                }
+                finally
+                {
+                    Marshal.FreeHGlobal(modulePtr);
+                    Marshal.FreeHGlobal(patternPtr);
+                }
 #endif
+            }
+        }
     }
 }
\ No newline at end of file
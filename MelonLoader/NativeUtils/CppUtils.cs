@@ -48,6 +48,21 @@ namespace MelonLoader.NativeUtils
             return new IntPtr(newPtrInt + delta);
         }
 
+        /// <summary>
+        /// Scans every executable segment of a loaded module, looked up by file name, for the signature.
+        /// </summary>
+        public static IntPtr[] SigscanAll(string moduleName, string signature)
+            => BootstrapInterop.NativeSigScan(moduleName, signature);
+
+        /// <summary>
+        /// Like <see cref="SigscanAll(string, string)"/>, but only returns the first match, or <see cref="IntPtr.Zero"/>.
+        /// </summary>
+        public static IntPtr Sigscan(string moduleName, string signature)
+        {
+            IntPtr[] matches = SigscanAll(moduleName, signature);
+            return matches.Length > 0 ? matches[0] : IntPtr.Zero;
+        }
+
         public static unsafe IntPtr[] SigscanAll(IntPtr module, int moduleSize, string signature)
         {
             List<IntPtr> ptrs = new List<IntPtr>();
@@ -0,0 +1,123 @@
+/* The MelonLoader native mod ABI. Generated from src/abi.rs by cbindgen, do not edit. */
+
+#ifndef MELONLOADER_H
+#define MELONLOADER_H
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+#define MELON_ABI_VERSION 1
+
+/**
+ * The stages of startup, in the order they happen.
+ */
+enum MelonLoaderPhase
+#ifdef __cplusplus
+  : uint8_t
+#endif // __cplusplus
+ {
+  MelonLoaderPhase_NotStarted,
+  MelonLoaderPhase_ConsoleInit,
+  MelonLoaderPhase_InitHooked,
+  MelonLoaderPhase_RuntimeInitialized,
+  MelonLoaderPhase_HostLoaded,
+  MelonLoaderPhase_PreStarted,
+  MelonLoaderPhase_Started,
+  MelonLoaderPhase_Failed,
+};
+#ifndef __cplusplus
+typedef uint8_t MelonLoaderPhase;
+#endif // __cplusplus
+
+typedef enum MelonLogLevel {
+  MelonLogLevel_Info,
+  MelonLogLevel_Warning,
+  MelonLogLevel_Error,
+  /**
+   * only shown in debug mode
+   */
+  MelonLogLevel_Debug,
+} MelonLogLevel;
+
+typedef enum MelonPath {
+  /**
+   * where MelonLoader's folders live
+   */
+  MelonPath_Base,
+  /**
+   * the game's install folder
+   */
+  MelonPath_Game,
+  MelonPath_UserData,
+  MelonPath_MelonLoader,
+  MelonPath_NativeMods,
+} MelonPath;
+
+/**
+ * Identifies a hook made with `melon_hook_attach`.
+ */
+typedef uint32_t MelonHookId;
+
+/**
+ * Called with the phase that was reached, and the `user_data` it was subscribed with.
+ */
+typedef void (*MelonPhaseCallback)(MelonLoaderPhase phase, void *user_data);
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+uint32_t melon_abi_version(void);
+
+/**
+ * Writes `message` to the console and the log file. `source` is shown in front of it, and can be null.
+ *
+ * # Safety
+ * `source` must be null or a valid C string, and `message` a valid C string.
+ */
+void melon_log(enum MelonLogLevel level,
+               const char *source,
+               const char *message);
+
+/**
+ * Hooks `target` with `detour`, writing the trampoline to call the original through into `trampoline`.
+ * Returns the hook's id, or 0 if it failed. `name` shows up in hook listings, and can be null.
+ *
+ * # Safety
+ * `name` must be null or a valid C string, and `trampoline` must be null or writable.
+ */
+MelonHookId melon_hook_attach(const char *name,
+                              void *target,
+                              void *detour,
+                              void **trampoline);
+
+/**
+ * Detaches a hook made with `melon_hook_attach`.
+ */
+bool melon_hook_detach(MelonHookId id);
+
+/**
+ * Copies one of MelonLoader's paths into `buffer`, null terminated.
+ * Returns the size needed including the terminator, nothing is written if `size` is smaller than that.
+ *
+ * # Safety
+ * `buffer` must be null or have room for `size` bytes.
+ */
+uintptr_t melon_get_path(enum MelonPath kind,
+                         char *buffer,
+                         uintptr_t size);
+
+MelonLoaderPhase melon_get_phase(void);
+
+/**
+ * Calls `callback` on every loader phase reached from now on. Returns false if `callback` is null.
+ */
+bool melon_phase_subscribe(MelonPhaseCallback callback, void *user_data);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* MELONLOADER_H */
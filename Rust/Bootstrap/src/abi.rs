@@ -0,0 +1,232 @@
+//! The C ABI native mods use to talk to the Bootstrap, declared in `include/melonloader.h`.
+//! The header is generated by `Rust/HeaderGen`, rerun it after changing anything here.
+//!
+//! Everything here is append only: `MELON_ABI_VERSION` goes up when something is added, and nothing
+//! that shipped changes its signature. Mods should check `melon_abi_version` before using newer calls.
+
+use std::{
+    collections::HashMap,
+    ffi::{c_char, c_void, CStr},
+    os::unix::ffi::OsStrExt,
+    path::PathBuf,
+    sync::{Mutex, Once},
+};
+
+use lazy_static::lazy_static;
+
+use crate::{
+    error,
+    hooks::{
+        registry::{self, HookId},
+        NativeHook,
+    },
+    logging::logger::{self, LogLevel},
+    melonenv::paths,
+    phase::{self, LoaderPhase},
+    utils::modules,
+};
+
+pub const MELON_ABI_VERSION: u32 = 1;
+
+/// Identifies a hook made with `melon_hook_attach`.
+// the same as registry::HookId, spelled out because the header is only generated from this file and phase.rs
+pub type MelonHookId = u32;
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub enum MelonLogLevel {
+    Info,
+    Warning,
+    Error,
+    /// only shown in debug mode
+    Debug,
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub enum MelonPath {
+    /// where MelonLoader's folders live
+    Base,
+    /// the game's install folder
+    Game,
+    UserData,
+    MelonLoader,
+    NativeMods,
+}
+
+/// Called with the phase that was reached, and the `user_data` it was subscribed with.
+pub type MelonPhaseCallback = Option<extern "C" fn(phase: LoaderPhase, user_data: *mut c_void)>;
+
+/// hooks don't care about the signature here, they are only attached and detached
+type AbiHook = NativeHook<fn()>;
+
+struct Subscriber {
+    callback: extern "C" fn(LoaderPhase, *mut c_void),
+    user_data: usize,
+}
+
+lazy_static! {
+    static ref HOOKS: Mutex<HashMap<HookId, AbiHook>> = Mutex::new(HashMap::new());
+    static ref SUBSCRIBERS: Mutex<Vec<Subscriber>> = Mutex::new(Vec::new());
+}
+
+static SUBSCRIBED: Once = Once::new();
+
+#[no_mangle]
+pub extern "C" fn melon_abi_version() -> u32 {
+    MELON_ABI_VERSION
+}
+
+/// Writes `message` to the console and the log file. `source` is shown in front of it, and can be null.
+///
+/// # Safety
+/// `source` must be null or a valid C string, and `message` a valid C string.
+#[no_mangle]
+pub unsafe extern "C" fn melon_log(level: MelonLogLevel, source: *const c_char, message: *const c_char) {
+    if message.is_null() {
+        return;
+    }
+
+    let message = CStr::from_ptr(message).to_string_lossy();
+    let message = if source.is_null() {
+        message.into_owned()
+    } else {
+        format!("[{}] {}", CStr::from_ptr(source).to_string_lossy(), message)
+    };
+
+    let level = match level {
+        MelonLogLevel::Info => LogLevel::Info,
+        MelonLogLevel::Warning => LogLevel::Warning,
+        MelonLogLevel::Error => LogLevel::Error,
+        MelonLogLevel::Debug => LogLevel::Debug,
+    };
+
+    let _ = logger::log_console_file(level, &message);
+}
+
+/// Hooks `target` with `detour`, writing the trampoline to call the original through into `trampoline`.
+/// Returns the hook's id, or 0 if it failed. `name` shows up in hook listings, and can be null.
+///
+/// # Safety
+/// `name` must be null or a valid C string, and `trampoline` must be null or writable.
+#[no_mangle]
+pub unsafe extern "C" fn melon_hook_attach(
+    name: *const c_char,
+    target: *mut c_void,
+    detour: *mut c_void,
+    trampoline: *mut *mut c_void,
+) -> MelonHookId {
+    let name = if name.is_null() {
+        modules::describe(target as usize)
+    } else {
+        CStr::from_ptr(name).to_string_lossy().into_owned()
+    };
+
+    // NativeHook wants a static name, mods only attach a handful of hooks so leaking it is fine
+    let name: &'static str = Box::leak(name.into_boxed_str());
+    let mut hook = AbiHook::new(name, target, detour).with_owner(registry::OWNER_NATIVE);
+
+    if let Err(e) = hook.hook() {
+        let _ = error!("Native mod failed to hook {}: {}", name, e.to_string());
+        return 0;
+    }
+
+    let Some(id) = hook.id else {
+        return 0;
+    };
+
+    if !trampoline.is_null() {
+        *trampoline = hook.trampoline;
+    }
+
+    if let Ok(mut hooks) = HOOKS.lock() {
+        hooks.insert(id, hook);
+    }
+
+    id
+}
+
+/// Detaches a hook made with `melon_hook_attach`.
+#[no_mangle]
+pub extern "C" fn melon_hook_detach(id: MelonHookId) -> bool {
+    let Ok(mut hooks) = HOOKS.lock() else {
+        return false;
+    };
+
+    let Some(hook) = hooks.remove(&id) else {
+        return false;
+    };
+
+    match hook.unhook() {
+        Ok(_) => true,
+        Err(e) => {
+            let _ = error!("Native mod failed to unhook {}: {}", hook.name, e.to_string());
+            hooks.insert(id, hook);
+            false
+        }
+    }
+}
+
+/// Copies one of MelonLoader's paths into `buffer`, null terminated.
+/// Returns the size needed including the terminator, nothing is written if `size` is smaller than that.
+///
+/// # Safety
+/// `buffer` must be null or have room for `size` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn melon_get_path(kind: MelonPath, buffer: *mut c_char, size: usize) -> usize {
+    let path: &PathBuf = match kind {
+        MelonPath::Base => &paths::BASE_DIR,
+        MelonPath::Game => &paths::GAME_DIR,
+        MelonPath::UserData => &paths::USER_DATA_FOLDER,
+        MelonPath::MelonLoader => &paths::MELONLOADER_FOLDER,
+        MelonPath::NativeMods => &paths::NATIVE_MODS_FOLDER,
+    };
+
+    let bytes = path.as_os_str().as_bytes();
+    let needed = bytes.len() + 1;
+
+    if !buffer.is_null() && size >= needed {
+        std::ptr::copy_nonoverlapping(bytes.as_ptr(), buffer as *mut u8, bytes.len());
+        *buffer.add(bytes.len()) = 0;
+    }
+
+    needed
+}
+
+#[no_mangle]
+pub extern "C" fn melon_get_phase() -> LoaderPhase {
+    phase::current()
+}
+
+/// Calls `callback` on every loader phase reached from now on. Returns false if `callback` is null.
+#[no_mangle]
+pub extern "C" fn melon_phase_subscribe(callback: MelonPhaseCallback, user_data: *mut c_void) -> bool {
+    let Some(callback) = callback else {
+        return false;
+    };
+
+    SUBSCRIBED.call_once(|| phase::subscribe(dispatch));
+
+    let Ok(mut subscribers) = SUBSCRIBERS.lock() else {
+        return false;
+    };
+
+    subscribers.push(Subscriber {
+        callback,
+        user_data: user_data as usize,
+    });
+
+    true
+}
+
+fn dispatch(phase: LoaderPhase) {
+    // copied out so a callback can subscribe again without deadlocking
+    let subscribers: Vec<(extern "C" fn(LoaderPhase, *mut c_void), usize)> = match SUBSCRIBERS.lock() {
+        Ok(subscribers) => subscribers.iter().map(|s| (s.callback, s.user_data)).collect(),
+        Err(_) => return,
+    };
+
+    for (callback, user_data) in subscribers {
+        callback(phase, user_data as *mut c_void);
+    }
+}
@@ -1,6 +1,11 @@
 use lazy_static::lazy_static;
+#[cfg(target_os = "android")]
 use libc::size_t;
-use netcorehost::{pdcstr, hostfxr::Hostfxr};
+use netcorehost::{
+    hostfxr::{Hostfxr, HostfxrContext, InitializedForRuntimeConfig},
+    pdcstr,
+};
+#[cfg(target_os = "android")]
 use unity_rs::{il2cpp::types::Il2CppThread, runtime::Runtime};
 use std::{
     ffi::{c_char, c_void},
@@ -9,7 +14,10 @@ use std::{
 };
 
 use crate::{
-    core_android, debug, errors::{dotneterr::DotnetErr, DynErr}, icalls, logging::logger, melonenv, utils::{self, strings::wide_str}
+    debug, errors::{dotneterr::DotnetErr, DynErr}, hooks::{self, registry, transaction::{self, TransactionId, TransactionResult}},
+    icalls::{self, bootstrap_interop::HookStatus}, logging::logger,
+    melonenv::{self, config::CONFIG}, platform, warn,
+    utils::{self, dotnet_versions, exports, modules, profiler, runtime_config::{self, RuntimeConfig}, strings::wide_str},
 };
 
 /// These are functions that MelonLoader.NativeHost.dll will fill in, once we call LoadStage1.
@@ -22,6 +30,7 @@ pub struct HostImports {
     pub initialize: fn(),
     pub pre_start: fn(),
     pub start: fn(),
+    pub shutdown: fn(),
 }
 
 /// These are functions that we will pass to MelonLoader.NativeHost.dll.
@@ -35,6 +44,34 @@ pub struct HostExports {
     pub log_console: unsafe fn(*const c_char),
     pub get_java_vm: unsafe fn() -> *mut *const c_void,
     pub get_package_name: unsafe fn() -> *const c_char,
+    pub get_loader_phase: extern "C" fn() -> u8,
+    pub get_failed_phase: extern "C" fn() -> u8,
+
+    pub hook_count: extern "C" fn() -> u32,
+    pub hook_get: unsafe extern "C" fn(u32, *mut registry::HookInfo) -> bool,
+    pub hook_detach_id: extern "C" fn(registry::HookId) -> bool,
+    pub hook_detach_all: extern "C" fn(),
+    pub hook_attach_plt: unsafe extern "C" fn(*const c_char, *const c_char, *mut c_void, *mut *mut c_void) -> registry::HookId,
+    pub hook_attach_ex: unsafe extern "C" fn(*mut *mut c_void, *mut c_void) -> HookStatus,
+    pub hook_detach_ex: unsafe extern "C" fn(*mut *mut c_void, *mut c_void) -> HookStatus,
+    pub hook_get_last_error: extern "C" fn() -> *const c_char,
+
+    pub transaction_begin: extern "C" fn() -> TransactionId,
+    pub transaction_attach: extern "C" fn(TransactionId, *mut *mut c_void, *mut c_void) -> bool,
+    pub transaction_detach: extern "C" fn(TransactionId, *mut *mut c_void, *mut c_void) -> bool,
+    pub transaction_commit: unsafe extern "C" fn(TransactionId, *mut TransactionResult, u32) -> bool,
+    pub transaction_rollback: extern "C" fn(TransactionId) -> bool,
+
+    pub sig_scan: unsafe extern "C" fn(*const c_char, *const c_char, *mut usize, u32) -> u32,
+
+    pub module_count: extern "C" fn() -> u32,
+    pub module_get: unsafe extern "C" fn(u32, *mut modules::ModuleInfo) -> bool,
+    pub module_resolve: unsafe extern "C" fn(usize, *mut u32, *mut usize) -> bool,
+    pub symbol_resolve: unsafe extern "C" fn(*const c_char, *const c_char) -> *mut c_void,
+
+    pub patch_write: unsafe extern "C" fn(*mut c_void, *const u8, usize) -> registry::HookId,
+    pub patch_nop: extern "C" fn(*mut c_void, usize) -> registry::HookId,
+    pub patch_write_pointer: extern "C" fn(*mut c_void, *mut c_void) -> registry::HookId,
 }
 
 // Initializing the host imports as a static variable. Later on this is replaced with a filled in version of the struct.
@@ -44,26 +81,33 @@ lazy_static! {
         initialize: || {},
         pre_start: || {},
         start: || {},
+        shutdown: || {},
     });
 }
 
 pub fn init() -> Result<(), DynErr> {
     let runtime_dir = melonenv::paths::runtime_dir()?;
 
-    let hostfxr_path = melonenv::paths::get_dotnet_path()?.join("host/fxr/8.0.6/libhostfxr.so");
-    let hostfxr = Hostfxr::load_from_path(hostfxr_path).map_err(|_| DotnetErr::FailedHostFXRLoad)?;
+    // validated up front, hostfxr only hands back an error code when it doesn't like the config
+    let config_path = runtime_dir.join(runtime_config::RUNTIME_CONFIG_NAME);
+    let runtime_config = RuntimeConfig::load(&config_path)?;
+    runtime_config.dump(&config_path)?;
+    dotnet_versions::resolve_framework(&runtime_config)?;
 
-    let config_path = runtime_dir.join("MelonLoader.runtimeconfig.json");
-    if !config_path.exists() {
-        return Err(DotnetErr::RuntimeConfig.into());
-    }
+    let hostfxr_path = dotnet_versions::hostfxr_path()?;
+    let hostfxr = profiler::measure("hostfxr_load", || Hostfxr::load_from_path(hostfxr_path))
+        .map_err(|_| DotnetErr::FailedHostFXRLoad)?;
 
     let dotnet_path = melonenv::paths::get_dotnet_path()?;
 
+    apply_runtime_environment()?;
+
     let context = hostfxr.initialize_for_runtime_config_with_dotnet_root(
         utils::strings::pdcstr(config_path)?,
         utils::strings::pdcstr(dotnet_path.to_path_buf())?)?;
 
+    apply_runtime_properties(&context)?;
+
     let loader = context.get_delegate_loader_for_assembly(utils::strings::pdcstr(
         runtime_dir.join("MelonLoader.NativeHost.dll"),
     )?)?;
@@ -78,21 +122,51 @@ pub fn init() -> Result<(), DynErr> {
         initialize: || {},
         pre_start: || {},
         start: || {},
+        shutdown: || {},
     };
 
     let mut exports = HostExports {
         hook_attach: icalls::bootstrap_interop::attach,
         hook_detach: icalls::bootstrap_interop::detach,
         log_console: logger::log_console_interop,
-        get_java_vm: core_android::get_raw_java_vm,
+        get_java_vm: platform::get_raw_java_vm,
         get_package_name: crate::melonenv::paths::get_package_name_raw,
+        get_loader_phase: crate::phase::melonloader_get_loader_phase,
+        get_failed_phase: crate::phase::melonloader_get_failed_phase,
+
+        hook_count: registry::hook_count,
+        hook_get: registry::hook_get,
+        hook_detach_id: registry::hook_detach_id,
+        hook_detach_all: registry::hook_detach_all,
+        hook_attach_plt: registry::hook_attach_plt,
+        hook_attach_ex: icalls::bootstrap_interop::attach_ex,
+        hook_detach_ex: icalls::bootstrap_interop::detach_ex,
+        hook_get_last_error: icalls::bootstrap_interop::get_last_error,
+
+        transaction_begin: transaction::transaction_begin,
+        transaction_attach: transaction::transaction_attach,
+        transaction_detach: transaction::transaction_detach,
+        transaction_commit: transaction::transaction_commit,
+        transaction_rollback: transaction::transaction_rollback,
+
+        sig_scan: utils::sigscan::sig_scan,
+
+        module_count: modules::module_count,
+        module_get: modules::module_get,
+        module_resolve: modules::module_resolve,
+        symbol_resolve: utils::symbols::symbol_resolve,
+
+        patch_write: hooks::patch::patch_write,
+        patch_nop: hooks::patch::patch_nop,
+        patch_write_pointer: hooks::patch::patch_write_pointer,
     };
 
+    #[cfg(target_os = "android")]
     apply_mono_patches()?;
 
     debug!("[Dotnet] Invoking LoadStage1")?;
     //MelonLoader.NativeHost will fill in the HostImports struct with pointers to functions
-    init(addr_of_mut!(imports));
+    profiler::measure("load_stage_1", || init(addr_of_mut!(imports)));
 
     debug!("[Dotnet] Reloading NativeHost into correct load context and getting LoadStage2 pointer")?;
 
@@ -113,13 +187,13 @@ pub fn init() -> Result<(), DynErr> {
     //turn the function pointer into a function we can invoke
     let init_stage_two: fn(*mut HostImports, *mut HostExports) =
         unsafe { std::mem::transmute(init_stage_two) };
-    init_stage_two(addr_of_mut!(imports), addr_of_mut!(exports));
+    profiler::measure("load_stage_2", || init_stage_two(addr_of_mut!(imports), addr_of_mut!(exports)));
 
     if addr_of!(imports.initialize).is_null() {
         Err("Failed to get HostImports::Initialize!")?
     }
 
-    (imports.initialize)();
+    profiler::measure("initialize", || (imports.initialize)());
 
     *IMPORTS.try_write()? = imports;
 
@@ -142,6 +216,52 @@ pub fn start() -> Result<(), DynErr> {
     Ok(())
 }
 
+pub fn shutdown() -> Result<(), DynErr> {
+    let imports = IMPORTS.try_read()?;
+
+    (imports.shutdown)();
+
+    Ok(())
+}
+
+/// The runtime reads its environment while initializing, so this has to happen before hostfxr is touched.
+fn apply_runtime_environment() -> Result<(), DynErr> {
+    for (key, value) in CONFIG.runtime.environment.iter() {
+        if !key.starts_with("DOTNET_") && !key.starts_with("COMPlus_") {
+            warn!("[Dotnet] {} is not a .NET environment variable, setting it anyway", key)?;
+        }
+
+        debug!("[Dotnet] Setting environment variable {}={}", key, value)?;
+        std::env::set_var(key, value);
+    }
+
+    Ok(())
+}
+
+/// Properties set here override the ones from the runtimeconfig, they're only read once the runtime starts.
+fn apply_runtime_properties(context: &HostfxrContext<InitializedForRuntimeConfig>) -> Result<(), DynErr> {
+    for (key, value) in CONFIG.runtime.properties.iter() {
+        let value = match value {
+            serde_json::Value::String(s) => s.clone(),
+            serde_json::Value::Bool(_) | serde_json::Value::Number(_) => value.to_string(),
+            _ => {
+                warn!("[Dotnet] Runtime property {} must be a string, boolean or number, skipping it", key)?;
+                continue;
+            }
+        };
+
+        debug!("[Dotnet] Setting runtime property {}={}", key, value)?;
+        context.set_runtime_property_value(
+            utils::strings::pdcstr(key)?,
+            utils::strings::pdcstr(&value)?,
+        )?;
+    }
+
+    Ok(())
+}
+
+/// The linux-bionic runtime is Mono with CoreCLR's hosting layer on top, desktop Linux gets a real CoreCLR.
+#[cfg(target_os = "android")]
 fn apply_mono_patches() -> Result<(), DynErr> {
     debug!("[Dotnet] Applying Mono runtime patches")?;
 
@@ -170,6 +290,7 @@ fn apply_mono_patches() -> Result<(), DynErr> {
     Ok(())
 }
 
+#[cfg(target_os = "android")]
 unsafe extern "C" fn mono_unhandled_exception(exc: *mut unity_rs::mono::types::MonoObject, user_data: *mut c_void) {
     let _ = user_data;
     if (exc as usize) == 0 {
@@ -181,6 +302,7 @@ unsafe extern "C" fn mono_unhandled_exception(exc: *mut unity_rs::mono::types::M
     print_unhandled_exception(exc);
 }
 
+#[cfg(target_os = "android")]
 fn mono_check_thread(tid: u64) -> bool {
     debug!("[Dotnet] Checking thread {:#x}", tid).unwrap();
 
@@ -188,7 +310,7 @@ fn mono_check_thread(tid: u64) -> bool {
 
     
     let mut size: usize = 0;
-    let get_all_attached_threads = unsafe { std::mem::transmute::<*mut c_void, fn(size: *mut size_t) -> *const *const Il2CppThread>(runtime.get_export_ptr("il2cpp_thread_get_all_attached_threads").unwrap()) };
+    let get_all_attached_threads = unsafe { std::mem::transmute::<*mut c_void, fn(size: *mut size_t) -> *const *const Il2CppThread>(exports::resolve(runtime, "il2cpp_thread_get_all_attached_threads").unwrap()) };
     let threads = get_all_attached_threads(addr_of_mut!(size));
     let threads_slice = unsafe { std::slice::from_raw_parts(threads, size) };
 
@@ -1,34 +1,90 @@
 use unity_rs::runtime::{FerrexRuntime, RuntimeType};
 
-use crate::{errors::DynErr, internal_failure, runtime};
+use crate::{errors::DynErr, icalls, phase::{self, LoaderPhase}, runtime, safe_mode, utils::profiler, warn};
 
 pub mod dotnet;
 pub mod mono;
 
 pub fn init(runtime: &FerrexRuntime) -> Result<(), DynErr> {
+    if safe_mode::is_engaged() {
+        return Ok(());
+    }
+
     match runtime.get_type() {
-        RuntimeType::Mono(_) => internal_failure!("Mono is unsupported."),
+        RuntimeType::Mono(_) => {
+            icalls::init(runtime)?;
+            mono::init(runtime)?;
+        }
 
-        RuntimeType::Il2Cpp(_) => dotnet::init(),
+        RuntimeType::Il2Cpp(_) => dotnet::init()?,
     }
+
+    phase::transition(LoaderPhase::HostLoaded)?;
+
+    Ok(())
 }
 
 pub fn pre_start() -> Result<(), DynErr> {
+    if safe_mode::is_engaged() {
+        return Ok(());
+    }
+
     let runtime = runtime!()?;
 
     match runtime.get_type() {
-        RuntimeType::Mono(_) => internal_failure!("Mono is unsupported."),
+        RuntimeType::Mono(_) => profiler::measure("pre_start", mono::pre_start)?,
 
-        RuntimeType::Il2Cpp(_) => dotnet::pre_start(),
+        RuntimeType::Il2Cpp(_) => profiler::measure("pre_start", dotnet::pre_start)?,
     }
+
+    phase::transition(LoaderPhase::PreStarted)?;
+
+    Ok(())
 }
 
 pub fn start() -> Result<(), DynErr> {
+    if safe_mode::is_engaged() {
+        return Ok(());
+    }
+
+    let runtime = runtime!()?;
+
+    match runtime.get_type() {
+        RuntimeType::Mono(_) => profiler::measure("start", mono::start)?,
+
+        RuntimeType::Il2Cpp(_) => profiler::measure("start", dotnet::start)?,
+    }
+
+    phase::transition(LoaderPhase::Started)?;
+
+    if let Err(e) = profiler::write_report() {
+        let _ = warn!("Failed to write the startup timing report: {}", e.to_string());
+    }
+
+    Ok(())
+}
+
+/// Lets MelonLoader save and unpatch before the process goes away.
+/// Does nothing unless the managed side actually got loaded.
+pub fn shutdown() -> Result<(), DynErr> {
+    if safe_mode::is_engaged() {
+        return Ok(());
+    }
+
+    if !matches!(
+        phase::current(),
+        LoaderPhase::HostLoaded | LoaderPhase::PreStarted | LoaderPhase::Started
+    ) {
+        return Ok(());
+    }
+
     let runtime = runtime!()?;
 
     match runtime.get_type() {
-        RuntimeType::Mono(_) => internal_failure!("Mono is unsupported."),
+        RuntimeType::Mono(_) => mono::shutdown()?,
 
-        RuntimeType::Il2Cpp(_) => dotnet::start(),
+        RuntimeType::Il2Cpp(_) => dotnet::shutdown()?,
     }
+
+    Ok(())
 }
@@ -1,4 +1,4 @@
-use std::{ptr::null_mut, sync::{RwLock, Mutex}};
+use std::{ffi::c_void, ptr::null_mut, sync::{RwLock, Mutex}};
 
 use lazy_static::lazy_static;
 use unity_rs::{
@@ -6,12 +6,13 @@ use unity_rs::{
     runtime::FerrexRuntime,
 };
 
-use crate::{debug, errors::DynErr, melonenv::{self, paths}, runtime};
+use crate::{debug, errors::DynErr, melonenv::{self, paths}, runtime, utils::exports};
 
 lazy_static! {
     pub static ref MONO_PRESTART: Mutex<UnityMethod> =
         Mutex::new(UnityMethod { inner: null_mut() });
     pub static ref MONO_START: Mutex<UnityMethod> = Mutex::new(UnityMethod { inner: null_mut() });
+    pub static ref MONO_QUIT: Mutex<UnityMethod> = Mutex::new(UnityMethod { inner: null_mut() });
     pub static ref ASSEMBLYMANAGER_RESOLVE: RwLock<UnityMethod> =
         RwLock::new(UnityMethod { inner: null_mut() });
     pub static ref ASSEMBLYMANAGER_LOADINFO: RwLock<UnityMethod> =
@@ -39,6 +40,7 @@ pub fn init(runtime: &FerrexRuntime) -> Result<(), DynErr> {
 
     let prestart_method = core_class.get_method("PreStart", 0, runtime)?;
     let start_method = core_class.get_method("Start", 0, runtime)?;
+    let quit_method = core_class.get_method("Quit", 0, runtime)?;
 
     //get the AssemblyManager class and grab some methods from it
     let assemblymanager_class = melonloader_assembly.get_class(
@@ -53,6 +55,7 @@ pub fn init(runtime: &FerrexRuntime) -> Result<(), DynErr> {
     //store the methods for later, in a thread safe global static.
     *MONO_PRESTART.try_lock()? = prestart_method;
     *MONO_START.try_lock()? = start_method;
+    *MONO_QUIT.try_lock()? = quit_method;
     *ASSEMBLYMANAGER_RESOLVE.try_write()? = resolve_method;
     *ASSEMBLYMANAGER_LOADINFO.try_write()? = loadinfo_method;
 
@@ -82,6 +85,23 @@ pub fn start() -> Result<(), DynErr> {
     Ok(())
 }
 
+pub fn shutdown() -> Result<(), DynErr> {
+    let quit_method = MONO_QUIT.try_lock()?;
+    if quit_method.inner.is_null() {
+        return Err("Quit method not found".into());
+    }
+
+    let runtime = runtime!()?;
+
+    // shutdown can be requested from the signal watcher thread, which mono doesn't know about yet
+    let get_root_domain = unsafe { std::mem::transmute::<*mut c_void, fn() -> *mut c_void>(exports::resolve(runtime, "mono_get_root_domain")?) };
+    let thread_attach = unsafe { std::mem::transmute::<*mut c_void, fn(*mut c_void) -> *mut c_void>(exports::resolve(runtime, "mono_thread_attach")?) };
+    thread_attach(get_root_domain());
+
+    let _ = quit_method.invoke(None, None, runtime)?;
+    Ok(())
+}
+
 fn preload(runtime: &FerrexRuntime) -> Result<(), DynErr> {
 	
     debug!("Initializing Preload")?;
@@ -1,14 +1,34 @@
-use crate::{console, errors::DynErr, hooks, internal_failure};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::{
+    base_assembly, console, errors::DynErr, hooks, log, logging::logger,
+    phase::{self, LoaderPhase}, recoverable_failure, utils::profiler, warn,
+};
+
+static SHUTTING_DOWN: AtomicBool = AtomicBool::new(false);
 
 #[no_mangle]
-fn startup() {
-    init().unwrap_or_else(|e| {
-        internal_failure!("Failed to initialize MelonLoader: {}", e.to_string());
-    })
+pub fn startup() {
+    profiler::start();
+
+    if let Err(e) = init() {
+        recoverable_failure!("Failed to initialize MelonLoader: {}", e.to_string());
+    }
 }
 
 fn init() -> Result<(), DynErr> {
     console::init()?;
+    phase::transition(LoaderPhase::ConsoleInit)?;
+
+    #[cfg(unix)]
+    if let Err(e) = crate::signals::init() {
+        warn!("Failed to install shutdown signal handlers: {}", e.to_string())?;
+    }
+
+    #[cfg(unix)]
+    if let Err(e) = crate::native_mods::init() {
+        warn!("Failed to load native mods: {}", e.to_string())?;
+    }
 
     hooks::init_hook::hook()?;
 
@@ -17,6 +37,35 @@ fn init() -> Result<(), DynErr> {
     Ok(())
 }
 
+/// Lets MelonLoader clean up, closes the log, detaches every hook, then exits the process.
+/// Only the first caller runs the sequence, any later request is ignored.
 pub fn shutdown() {
-    std::process::exit(0);
+    if prepare_shutdown() {
+        std::process::exit(0);
+    }
+}
+
+/// Runs the shutdown sequence without exiting, for callers that want to hand the exit to someone else.
+/// Returns false if the sequence already ran.
+pub fn prepare_shutdown() -> bool {
+    if SHUTTING_DOWN.swap(true, Ordering::SeqCst) {
+        return false;
+    }
+
+    log!("Shutting down");
+
+    if let Err(e) = base_assembly::shutdown() {
+        let _ = warn!("Failed to notify MelonLoader of shutdown: {}", e.to_string());
+    }
+
+    hooks::detach_all();
+    logger::close();
+
+    true
+}
+
+/// Exported under a prefixed name, a plain `shutdown` symbol would interpose libc's.
+#[no_mangle]
+pub fn melonloader_shutdown() {
+    shutdown();
 }
@@ -1,4 +1,4 @@
-use crate::{log, melonenv::paths, utils::{apk_asset_manager::{copy_melonloader_data, get_apk_modification_date}, perm_requester}};
+use crate::{log, melonenv::paths, utils::{apk_asset_manager::{copy_melonloader_data, get_apk_modification_date}, perm_requester, profiler}};
 use jni::{
     sys::{jint, JNI_VERSION_1_6},
     JNIEnv, JavaVM,
@@ -23,7 +23,7 @@ pub extern "system" fn JNI_OnLoad(vm: JavaVM, _: *mut c_void) -> jint {
 
     paths::cache_data_dir(&mut env);
 
-    perm_requester::ensure_perms(&mut env);
+    profiler::measure("permission_request", || perm_requester::ensure_perms(&mut env));
 
     let melonloader_path = paths::MELONLOADER_FOLDER.clone().0;
     if std::fs::metadata(&melonloader_path).is_err() {
@@ -44,7 +44,7 @@ pub extern "system" fn JNI_OnLoad(vm: JavaVM, _: *mut c_void) -> jint {
     log!("JNI initialized!");
     
     let lemon_patch_date = get_apk_modification_date(&mut env).unwrap();
-    copy_melonloader_data(&mut env, lemon_patch_date).unwrap();
+    profiler::measure("copy_melonloader_data", || copy_melonloader_data(&mut env, lemon_patch_date)).unwrap();
 
     log!("APK assets copied!");
 
@@ -0,0 +1,129 @@
+//! Desktop Linux entry point.
+//!
+//! There is no proxy on desktop, Bootstrap gets injected through `LD_PRELOAD` instead.
+//! UnityPlayer only dlopens the scripting runtime once it's already running, so we hook dlopen
+//! and kick off the regular startup as soon as GameAssembly.so or Mono shows up.
+
+use ctor::ctor;
+use lazy_static::lazy_static;
+use std::{
+    ffi::{c_char, c_int, c_void, CStr, CString},
+    path::Path,
+    ptr::null_mut,
+    sync::{Once, RwLock},
+};
+
+use crate::{
+    core, debug, errors::DynErr, hooks::NativeHook, internal_failure, log,
+    logging::logger::{self, LogLevel}, melonenv::paths,
+};
+
+type DlopenFn = extern "C" fn(*const c_char, c_int) -> *mut c_void;
+
+/// The names UnityPlayer uses when loading the scripting backend.
+const RUNTIME_LIBS: [&str; 4] = ["GameAssembly.so", "libil2cpp.so", "libmonobdwgc-2.0.so", "libmono.so"];
+
+lazy_static! {
+    static ref DLOPEN_HOOK: RwLock<NativeHook<DlopenFn>> =
+        RwLock::new(NativeHook::new("dlopen", null_mut(), null_mut()));
+}
+
+static STARTUP: Once = Once::new();
+
+#[ctor]
+fn on_load() {
+    // LD_PRELOAD is inherited by every child process, only ever initialize inside a Unity player.
+    if !is_unity_player() {
+        return;
+    }
+
+    let melonloader_path = paths::MELONLOADER_FOLDER.clone().0;
+    if std::fs::metadata(&melonloader_path).is_err() {
+        // the log file lives in there, so the console is all we have if this fails
+        if let Err(e) = std::fs::create_dir_all(&melonloader_path) {
+            crate::log_console!(
+                LogLevel::Error,
+                "Failed to create {}, MelonLoader will not load: {}",
+                melonloader_path.display(),
+                e
+            );
+            return;
+        }
+    }
+
+    logger::init().expect("Failed to initialize logger!");
+
+    log!("Bootstrap loaded into {}", paths::GAME_DIR.display());
+
+    hook_dlopen().unwrap_or_else(|e| {
+        internal_failure!("Failed to hook dlopen: {}", e.to_string());
+    });
+}
+
+fn hook_dlopen() -> Result<(), DynErr> {
+    debug!("Attaching hook to dlopen")?;
+
+    let name = CString::new("dlopen")?;
+    let dlopen = unsafe { libc::dlsym(libc::RTLD_DEFAULT, name.as_ptr()) };
+
+    let mut dlopen_hook = DLOPEN_HOOK.try_write()?;
+    *dlopen_hook = NativeHook::new("dlopen", dlopen, dlopen_detour as *mut c_void);
+
+    dlopen_hook.hook()?;
+
+    Ok(())
+}
+
+fn dlopen_detour(filename: *const c_char, flags: c_int) -> *mut c_void {
+    let trampoline = DLOPEN_HOOK.try_read().unwrap_or_else(|e| {
+        internal_failure!("Failed to read dlopen hook: {e}");
+    });
+
+    let handle = trampoline(filename, flags);
+
+    if handle.is_null() || filename.is_null() || !is_runtime_lib(filename) {
+        return handle;
+    }
+
+    STARTUP.call_once(|| {
+        let _ = debug!("Detaching hook from dlopen");
+        trampoline.unhook().unwrap_or_else(|e| {
+            internal_failure!("Failed to detach hook from dlopen: {}", e.to_string());
+        });
+
+        core::startup();
+    });
+
+    handle
+}
+
+fn is_runtime_lib(filename: *const c_char) -> bool {
+    let filename = unsafe { CStr::from_ptr(filename) }.to_string_lossy();
+
+    match Path::new(filename.as_ref()).file_name() {
+        Some(name) => RUNTIME_LIBS.iter().any(|lib| name == *lib),
+        None => false,
+    }
+}
+
+fn is_unity_player() -> bool {
+    let Ok(file_path) = std::env::current_exe() else { return false };
+
+    match (file_path.parent(), file_path.file_stem()) {
+        (Some(base_folder), Some(file_name)) => base_folder
+            .join(format!("{}_Data", file_name.to_string_lossy()))
+            .is_dir(),
+        _ => false,
+    }
+}
+
+/// There's no JVM on desktop, so there is nothing to hand out.
+pub unsafe fn get_raw_java_vm() -> *mut *const c_void {
+    null_mut()
+}
+
+#[no_mangle]
+pub extern "C" fn melonloader_print_string(input: *const c_char) {
+    let input = unsafe { CStr::from_ptr(input) }.to_string_lossy();
+    crate::log_console!(LogLevel::Info, "{}", input);
+}
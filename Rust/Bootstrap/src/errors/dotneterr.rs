@@ -1,10 +1,43 @@
+use std::path::PathBuf;
+
 use thiserror::Error;
 
+use crate::utils::dotnet_versions::RollForward;
+
 #[derive(Error, Debug)]
 pub enum DotnetErr {
     #[error("Failed to load hostfxr. Please make sure you have installed the .NET 6.0 runtime.")]
     FailedHostFXRLoad,
 
     #[error("Failed to find MelonLoader.runtimeconfig.json. Please reinstall MelonLoader.")]
-    RuntimeConfig
-}
\ No newline at end of file
+    RuntimeConfig,
+
+    #[error("Failed to read MelonLoader.runtimeconfig.json: {0}")]
+    UnreadableRuntimeConfig(std::io::Error),
+
+    #[error("MelonLoader.runtimeconfig.json is malformed: {0}")]
+    MalformedRuntimeConfig(#[from] serde_json::Error),
+
+    #[error("MelonLoader.runtimeconfig.json does not reference any framework. Please reinstall MelonLoader.")]
+    MissingFramework,
+
+    #[error("MelonLoader.runtimeconfig.json requests {name} with an invalid version '{version}'")]
+    InvalidFrameworkVersion { name: String, version: String },
+
+    #[error("MelonLoader.runtimeconfig.json uses an unknown roll forward policy '{0}'")]
+    UnknownRollForward(String),
+
+    #[error("MelonLoader.runtimeconfig.json has conflicting properties: {0}")]
+    ConflictingRuntimeConfig(String),
+
+    #[error("No hostfxr found in {0}. Please make sure the .NET runtime is installed.")]
+    NoHostFxr(PathBuf),
+
+    #[error("No installed {name} is compatible with {requested} (roll forward: {roll_forward}). Installed versions: [{available}]")]
+    NoCompatibleFramework {
+        name: String,
+        requested: String,
+        roll_forward: RollForward,
+        available: String,
+    },
+}
@@ -0,0 +1,11 @@
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum ExportError {
+    #[error("Could not resolve runtime export(s) {}, map them in UserData/ExportMap.json", .0.join(", "))]
+    Unresolved(Vec<String>),
+    #[error("Pattern for {name} matched {count} times, it has to match exactly once")]
+    AmbiguousPattern { name: String, count: usize },
+    #[error("No module to scan for {0}, set \"module\" in its mapping")]
+    NoModule(String),
+}
@@ -21,4 +21,8 @@ pub enum HookError {
     NoTrampoline(String),
     #[error("Failed to hook {0}")]
     Failed(String),
+    #[error("{0} is not hooked")]
+    NotHooked(String),
+    #[error("{0} is not in executable memory")]
+    NotExecutable(String),
 }
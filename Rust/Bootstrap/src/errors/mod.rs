@@ -4,4 +4,10 @@ pub mod conerr;
 pub mod hookerr;
 pub mod logerr;
 pub mod dotneterr;
-pub mod melonerr;
\ No newline at end of file
+pub mod exporterr;
+pub mod melonerr;
+pub mod patcherr;
+pub mod phaseerr;
+pub mod sigscanerr;
+pub mod signalerr;
+pub mod symbolerr;
\ No newline at end of file
@@ -0,0 +1,21 @@
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum PatchError {
+    #[error("Patch address is a Nullpointer")]
+    Nullpointer,
+    #[error("Patch at {0:#x} is empty")]
+    Empty(usize),
+    #[error("NOP length {0} is not a multiple of the instruction size {1}")]
+    MisalignedNop(usize, usize),
+    #[error("{0:#x} is not mapped")]
+    Unmapped(usize),
+    #[error("Failed to change page protection at {0:#x}: {1}")]
+    Protect(usize, String),
+    #[error("Patch at {0:#x} overlaps an existing patch")]
+    Overlap(usize),
+    #[error("Nothing is patched at {0:#x}")]
+    NotPatched(usize),
+    #[error("The patch table is poisoned, a previous patch panicked")]
+    Poisoned,
+}
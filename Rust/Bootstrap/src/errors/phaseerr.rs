@@ -0,0 +1,9 @@
+use thiserror::Error;
+
+use crate::phase::LoaderPhase;
+
+#[derive(Debug, Error)]
+pub enum PhaseError {
+    #[error("Invalid loader phase transition from {0:?} to {1:?}")]
+    InvalidTransition(LoaderPhase, LoaderPhase),
+}
@@ -0,0 +1,10 @@
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum SignalError {
+    #[error("Failed to create the signal pipe!")]
+    FailedToCreatePipe,
+
+    #[error("Failed to install a handler for signal {0}!")]
+    FailedToInstallHandler(i32),
+}
@@ -0,0 +1,13 @@
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum SigScanError {
+    #[error("Signature is empty")]
+    EmptyPattern,
+    #[error("Invalid signature byte '{0}'")]
+    InvalidByte(String),
+    #[error("Signature has no fixed bytes")]
+    OnlyWildcards,
+    #[error("Module {0} is not loaded")]
+    ModuleNotFound(String),
+}
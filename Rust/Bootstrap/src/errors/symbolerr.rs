@@ -0,0 +1,13 @@
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum SymbolError {
+    #[error("Module {0} is not loaded")]
+    ModuleNotFound(String),
+    #[error("{0} is not an ELF file for this architecture")]
+    NotElf(String),
+    #[error("{0} is truncated or malformed")]
+    Malformed(String),
+    #[error("Symbol {symbol} not found in {module}")]
+    SymbolNotFound { module: String, symbol: String },
+}
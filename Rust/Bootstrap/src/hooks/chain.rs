@@ -0,0 +1,157 @@
+//! Lets several detours share one target.
+//!
+//! The target itself is hooked once, pointing at a head stub. Every detour gets its own stub as its
+//! trampoline, which leads to the next detour in line, and the last one leads to the original function.
+//! Adding or removing a detour only retargets stubs, so the other detours and their trampolines are
+//! never disturbed. The head hook is internal to the chain and never shows up in the registry, only
+//! the chained detours do.
+
+use std::{
+    collections::{hash_map::Entry, HashMap},
+    sync::Mutex,
+};
+
+use lazy_static::lazy_static;
+
+use crate::{
+    debug,
+    errors::{hookerr::HookError, DynErr},
+    hooks::{
+        functions,
+        stubs::{self, Stub},
+    },
+};
+
+#[derive(Debug)]
+struct ChainEntry {
+    detour: usize,
+    /// the trampoline handed to whoever attached `detour`
+    next: Stub,
+}
+
+#[derive(Debug)]
+struct Chain {
+    /// the trampoline of the head hook, leading to the original function
+    original: usize,
+    head: Stub,
+    entries: Vec<ChainEntry>,
+}
+
+impl Chain {
+    /// Points whatever comes before `index` at `target`.
+    fn relink(&self, index: usize, target: usize) {
+        match index {
+            0 => self.head.set_target(target),
+            _ => self.entries[index - 1].next.set_target(target),
+        }
+    }
+}
+
+lazy_static! {
+    static ref CHAINS: Mutex<HashMap<usize, Chain>> = Mutex::new(HashMap::new());
+}
+
+/// Appends `detour` to the chain on `target`, creating the chain if needed.
+/// Returns the trampoline `detour` should call to continue down the chain.
+pub fn attach(target: usize, detour: usize) -> Result<usize, DynErr> {
+    attach_at(target, detour, usize::MAX)
+}
+
+/// Like `attach`, but puts `detour` at `index` in the chain, or at the end if the chain is shorter.
+/// Rolling back a detach uses this to put the detour back where it was.
+pub fn attach_at(target: usize, detour: usize, index: usize) -> Result<usize, DynErr> {
+    if target == 0 {
+        return Err(HookError::Nullpointer("target".to_string()).into());
+    }
+
+    if detour == 0 {
+        return Err(HookError::Nullpointer("detour".to_string()).into());
+    }
+
+    let mut chains = CHAINS.lock().map_err(|_| HookError::Failed(format!("{target:#x}")))?;
+
+    if chains.get(&target).is_some_and(|c| c.entries.iter().any(|e| e.detour == detour)) {
+        return Err(HookError::Failed(format!("{target:#x}, {detour:#x} is already attached to it")).into());
+    }
+
+    // built before anything goes live, so failing to allocate it leaves the target untouched
+    let next = stubs::allocate(0)?;
+    let trampoline = next.code;
+
+    let chain = match chains.entry(target) {
+        Entry::Occupied(entry) => entry.into_mut(),
+        Entry::Vacant(entry) => {
+            // passes straight through to the original until `detour` is linked in below
+            let head = stubs::allocate(next.code)?;
+            let original = functions::hook(target, head.code)?;
+            next.set_target(original);
+
+            entry.insert(Chain { original, head, entries: Vec::new() })
+        }
+    };
+
+    let index = index.min(chain.entries.len());
+    let after = chain.entries.get(index).map_or(chain.original, |e| e.detour);
+
+    // the new stub leads onwards before anything is pointed at the new detour
+    next.set_target(after);
+    chain.relink(index, detour);
+    chain.entries.insert(index, ChainEntry { detour, next });
+
+    debug!("Chained detour {:#x} onto {:#x} ({} in chain)", detour, target, chain.entries.len())?;
+
+    Ok(trampoline)
+}
+
+/// Takes `detour` out of the chain on `target`, unhooking the target once the chain is empty.
+pub fn detach(target: usize, detour: usize) -> Result<(), DynErr> {
+    let mut chains = CHAINS.lock().map_err(|_| HookError::Failed(format!("{target:#x}")))?;
+
+    let chain = chains
+        .get_mut(&target)
+        .ok_or_else(|| HookError::Failed(format!("{target:#x}, it has no chained detours")))?;
+
+    let index = chain
+        .entries
+        .iter()
+        .position(|e| e.detour == detour)
+        .ok_or_else(|| HookError::Failed(format!("{target:#x}, {detour:#x} is not attached to it")))?;
+
+    let after = chain
+        .entries
+        .get(index + 1)
+        .map_or(chain.original, |e| e.detour);
+
+    // the removed stub keeps pointing onwards, a thread could still be inside the detour
+    chain.relink(index, after);
+    chain.entries.remove(index);
+
+    debug!("Unchained detour {:#x} from {:#x} ({} left)", detour, target, chain.entries.len())?;
+
+    if chain.entries.is_empty() {
+        functions::unhook(target)?;
+        chains.remove(&target);
+    }
+
+    Ok(())
+}
+
+/// Where `detour` sits in the chain on `target`, 0 being the first to run.
+pub fn position(target: usize, detour: usize) -> Option<usize> {
+    CHAINS
+        .lock()
+        .ok()?
+        .get(&target)?
+        .entries
+        .iter()
+        .position(|e| e.detour == detour)
+}
+
+/// The detours on `target`, in the order they run.
+pub fn detours(target: usize) -> Vec<usize> {
+    CHAINS
+        .lock()
+        .ok()
+        .and_then(|chains| chains.get(&target).map(|c| c.entries.iter().map(|e| e.detour).collect()))
+        .unwrap_or_default()
+}
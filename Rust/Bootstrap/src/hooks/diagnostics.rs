@@ -0,0 +1,80 @@
+//! Dumps what a hook target looks like when hooking it fails, so user logs are enough to diagnose it.
+
+use capstone::prelude::*;
+
+use crate::{debug, debug_enabled, errors::DynErr, hooks::functions, utils::{maps, modules}};
+
+/// How many bytes of the target get dumped.
+const DUMP_LEN: usize = 32;
+
+/// How many instructions get disassembled at most.
+const MAX_INSTRUCTIONS: usize = 8;
+
+/// Logs the target's module and offset, its first bytes, and their disassembly. Only does anything in debug mode.
+pub fn dump_target(target: usize) {
+    if !debug_enabled!() {
+        return;
+    }
+
+    if let Err(e) = try_dump(target) {
+        let _ = debug!("Failed to dump hook target {:#x}: {}", target, e.to_string());
+    }
+}
+
+fn try_dump(target: usize) -> Result<(), DynErr> {
+    let maps = maps::read()?;
+    let _ = debug!("Hook target: {}", modules::describe_in(&maps, functions::code_address(target)));
+
+    let start = functions::code_address(target);
+    let Some(entry) = maps.iter().find(|m| m.contains(start)).filter(|m| m.perms.read) else {
+        let _ = debug!("Target is not readable, nothing to dump");
+        return Ok(());
+    };
+
+    let len = DUMP_LEN.min(entry.end - start);
+    let code = unsafe { std::slice::from_raw_parts(start as *const u8, len) };
+
+    let bytes = code.iter().map(|b| format!("{b:02X}")).collect::<Vec<_>>().join(" ");
+    let _ = debug!("First {} bytes: {}", len, bytes);
+
+    let cs = disassembler(target)?;
+    let instructions = cs.disasm_count(code, start as u64, MAX_INSTRUCTIONS)?;
+
+    for insn in instructions.as_ref() {
+        let _ = debug!(
+            "  {:#x}: {} {}",
+            insn.address(),
+            insn.mnemonic().unwrap_or("??"),
+            insn.op_str().unwrap_or_default()
+        );
+    }
+
+    Ok(())
+}
+
+#[cfg(target_arch = "x86_64")]
+fn disassembler(_target: usize) -> CsResult<Capstone> {
+    Capstone::new().x86().mode(arch::x86::ArchMode::Mode64).build()
+}
+
+#[cfg(target_arch = "x86")]
+fn disassembler(_target: usize) -> CsResult<Capstone> {
+    Capstone::new().x86().mode(arch::x86::ArchMode::Mode32).build()
+}
+
+#[cfg(target_arch = "aarch64")]
+fn disassembler(_target: usize) -> CsResult<Capstone> {
+    Capstone::new().arm64().mode(arch::arm64::ArchMode::Arm).build()
+}
+
+#[cfg(target_arch = "arm")]
+fn disassembler(target: usize) -> CsResult<Capstone> {
+    // the low bit marks thumb code
+    let mode = if target & 1 == 1 {
+        arch::arm::ArchMode::Thumb
+    } else {
+        arch::arm::ArchMode::Arm
+    };
+
+    Capstone::new().arm().mode(mode).build()
+}
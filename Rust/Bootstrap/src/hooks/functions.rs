@@ -1,4 +1,13 @@
-use crate::errors::{hookerr::HookError, DynErr};
+use crate::{
+    debug,
+    errors::{hookerr::HookError, DynErr},
+    hooks::{diagnostics, prologue},
+    utils::{
+        maps::{self, MapEntry},
+        modules,
+    },
+    warn,
+};
 
 pub fn hook(target: usize, detour: usize) -> Result<usize, HookError> {
     if target == 0 {
@@ -9,16 +18,22 @@ pub fn hook(target: usize, detour: usize) -> Result<usize, HookError> {
         return Err(HookError::Nullpointer("detour".to_string()));
     }
 
+    validate(target, detour)?;
+
     unsafe {
         let trampoline = dobby_rs::hook(target as dobby_rs::Address, detour as dobby_rs::Address)
-            .map_err(|e| HookError::Failed(e.to_string()));
+            .map_err(HookError::Dobby);
 
         let trampoline = match trampoline {
             Ok(t) => t,
-            Err(e) => return Err(e),
+            Err(e) => {
+                diagnostics::dump_target(target);
+                return Err(e);
+            }
         };
 
         if trampoline.is_null() {
+            diagnostics::dump_target(target);
             return Err(HookError::Null);
         }
 
@@ -33,8 +48,66 @@ pub fn unhook(target: usize) -> Result<(), DynErr> {
     }
 
     unsafe {
-        dobby_rs::unhook(target as dobby_rs::Address)?;
+        dobby_rs::unhook(target as dobby_rs::Address).map_err(HookError::Dobby)?;
+    }
+
+    Ok(())
+}
+
+/// Makes sure both ends of a hook are mapped executable, and warns if the target already starts with a jump.
+fn validate(target: usize, detour: usize) -> Result<(), HookError> {
+    let maps = match maps::read() {
+        Ok(maps) => maps,
+        Err(e) => {
+            let _ = warn!("Skipping hook validation, failed to read memory maps: {}", e.to_string());
+            return Ok(());
+        }
+    };
+
+    let target_entry = executable_entry(&maps, target)?;
+    executable_entry(&maps, detour)?;
+
+    let _ = debug!(
+        "Hooking {} with {}",
+        modules::describe_in(&maps, code_address(target)),
+        modules::describe_in(&maps, code_address(detour))
+    );
+
+    if target_entry.perms.read && already_branches(target, target_entry) {
+        let _ = warn!(
+            "{} already starts with a jump, something else may have hooked it",
+            modules::describe_in(&maps, code_address(target))
+        );
     }
 
     Ok(())
 }
+
+fn executable_entry(maps: &[MapEntry], address: usize) -> Result<&MapEntry, HookError> {
+    maps.iter()
+        .find(|m| m.contains(code_address(address)))
+        .filter(|m| m.perms.execute)
+        .ok_or_else(|| HookError::NotExecutable(modules::describe_in(maps, code_address(address))))
+}
+
+fn already_branches(address: usize, entry: &MapEntry) -> bool {
+    let start = code_address(address);
+    let len = prologue::PROLOGUE_LEN.min(entry.end - start);
+    let code = unsafe { std::slice::from_raw_parts(start as *const u8, len) };
+
+    #[cfg(target_arch = "arm")]
+    if address & 1 == 1 {
+        return prologue::is_thumb_branch(code);
+    }
+
+    prologue::is_branch(code)
+}
+
+/// Strips the thumb bit, arm addresses with the low bit set point at thumb code.
+pub fn code_address(address: usize) -> usize {
+    if cfg!(target_arch = "arm") {
+        address & !1
+    } else {
+        address
+    }
+}
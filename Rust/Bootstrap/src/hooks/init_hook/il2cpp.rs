@@ -1,30 +1,37 @@
-use std::{ffi::c_char, sync::RwLock, ptr::null_mut};
+use std::{ffi::{c_char, c_void}, sync::RwLock, ptr::null_mut};
 
 use lazy_static::lazy_static;
-use unity_rs::il2cpp::types::Il2CppDomain;
+use unity_rs::{il2cpp::types::Il2CppDomain, runtime::FerrexRuntime};
 
 use crate::{
     console, constants::InitFnIl2Cpp, debug, errors::DynErr, hooks::{NativeHook, invoke_hook},
-    internal_failure,
+    internal_failure, log, phase::{self, LoaderPhase}, recoverable_failure, utils::exports,
 };
 
 lazy_static! {
     pub static ref INIT_HOOK: RwLock<NativeHook<InitFnIl2Cpp>> =
-        RwLock::new(NativeHook::new(null_mut(), null_mut()));
+        RwLock::new(NativeHook::new("il2cpp_init", null_mut(), null_mut()));
 }
 
 pub fn detour(name: *const c_char) -> *mut Il2CppDomain {
-    detour_inner(name).unwrap_or_else(|e| {
+    let trampoline = INIT_HOOK.try_read().unwrap_or_else(|e| {
         internal_failure!("il2cpp_init detour failed: {e}");
-    })
-}
-
-fn detour_inner(name: *const c_char) -> Result<*mut Il2CppDomain, DynErr> {
-    console::set_handles()?;
+    });
 
-    let trampoline = INIT_HOOK.try_read()?;
+    let handles = console::set_handles();
     let domain = trampoline(name);
 
+    if let Err(e) = handles.and_then(|_| detour_inner(&trampoline)) {
+        recoverable_failure!("il2cpp_init detour failed: {e}");
+    }
+
+    domain
+}
+
+fn detour_inner(trampoline: &NativeHook<InitFnIl2Cpp>) -> Result<(), DynErr> {
+    phase::transition(LoaderPhase::RuntimeInitialized)?;
+    invoke_hook::trigger::set_main_thread()?;
+
     crate::base_assembly::init(crate::runtime!()?)?;
 
     debug!("Detaching hook from il2cpp_init")?;
@@ -32,5 +39,42 @@ fn detour_inner(name: *const c_char) -> Result<*mut Il2CppDomain, DynErr> {
     
     invoke_hook::hook()?;
 
-    Ok(domain)
+    Ok(())
+}
+
+/// The domain, if il2cpp_init already ran before we were loaded.
+pub fn initialized_domain(runtime: &FerrexRuntime) -> Option<*mut Il2CppDomain> {
+    // il2cpp_get_corlib only reads what init filled in, unlike il2cpp_domain_get, which allocates the domain if there is none yet
+    let get_corlib = exports::resolve(runtime, "il2cpp_get_corlib").ok()?;
+    let get_corlib = unsafe { std::mem::transmute::<*mut c_void, fn() -> *mut c_void>(get_corlib) };
+
+    if get_corlib().is_null() {
+        return None;
+    }
+
+    let domain_get = exports::resolve(runtime, "il2cpp_domain_get").ok()?;
+    let domain_get = unsafe { std::mem::transmute::<*mut c_void, fn() -> *mut Il2CppDomain>(domain_get) };
+
+    let domain = domain_get();
+    (!domain.is_null()).then_some(domain)
+}
+
+/// Picks up where the il2cpp_init detour would have, for when we got loaded after it already ran.
+pub fn late_attach(runtime: &FerrexRuntime, domain: *mut Il2CppDomain) -> Result<(), DynErr> {
+    log!("il2cpp_init already ran, late-attaching");
+
+    phase::transition(LoaderPhase::RuntimeInitialized)?;
+
+    // we're on whichever thread loaded us, not the game's main thread, so il2cpp has to be told about it,
+    // and the main thread is left for the game thread's first invoke to fill in
+    invoke_hook::trigger::await_main_thread()?;
+
+    let thread_attach = unsafe { std::mem::transmute::<*mut c_void, fn(*mut Il2CppDomain) -> *mut c_void>(exports::resolve(runtime, "il2cpp_thread_attach")?) };
+    thread_attach(domain);
+
+    crate::base_assembly::init(runtime)?;
+
+    invoke_hook::hook()?;
+
+    Ok(())
 }
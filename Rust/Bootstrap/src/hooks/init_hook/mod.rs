@@ -1,10 +1,12 @@
 mod il2cpp;
+mod mono;
 
 use crate::{
     debug,
     errors::DynErr,
+    phase::{self, LoaderPhase},
     runtime,
-    internal_failure,
+    utils::exports,
 };
 use std::ffi::c_void;
 use unity_rs::runtime::RuntimeType;
@@ -14,21 +16,50 @@ use super::NativeHook;
 pub fn hook() -> Result<(), DynErr> {
     let runtime = runtime!()?;
 
+    // fail with every missing export at once, rather than one by one as they get used
+    exports::check(runtime)?;
+
+    if let RuntimeType::Il2Cpp(_) = runtime.get_type() {
+        if let Some(domain) = il2cpp::initialized_domain(runtime) {
+            phase::transition(LoaderPhase::InitHooked)?;
+            return il2cpp::late_attach(runtime, domain);
+        }
+    }
+
     match runtime.get_type() {
-        RuntimeType::Mono(_) => internal_failure!("Mono is unsupported."),
+        RuntimeType::Mono(_) => {
+            debug!("Attaching hook to mono_jit_init_version")?;
+
+            let init_function = exports::resolve(runtime, "mono_jit_init_version")?;
+            let detour = mono::detour as usize;
+
+            let mut init_hook = mono::INIT_HOOK.try_write()?;
+            *init_hook = NativeHook::new("mono_jit_init_version", init_function, detour as *mut c_void);
+
+            init_hook.hook()?;
+        }
 
         RuntimeType::Il2Cpp(_) => {
             debug!("Attaching hook to il2cpp_init")?;
 
-            let init_function = runtime.get_export_ptr("il2cpp_init")?;
+            let init_function = exports::resolve(runtime, "il2cpp_init")?;
             let detour = il2cpp::detour as usize;
 
             let mut init_hook = il2cpp::INIT_HOOK.try_write()?;
-            *init_hook = NativeHook::new(init_function, detour as *mut c_void);
+            *init_hook = NativeHook::new("il2cpp_init", init_function, detour as *mut c_void);
 
             init_hook.hook()?;
         }
     };
 
+    phase::transition(LoaderPhase::InitHooked)?;
+
+    Ok(())
+}
+
+pub fn unhook() -> Result<(), DynErr> {
+    il2cpp::INIT_HOOK.try_read()?.unhook()?;
+    mono::INIT_HOOK.try_read()?.unhook()?;
+
     Ok(())
 }
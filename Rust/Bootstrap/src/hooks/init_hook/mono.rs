@@ -0,0 +1,57 @@
+use std::{ffi::{c_char, c_void}, sync::RwLock, ptr::null_mut};
+
+use lazy_static::lazy_static;
+use unity_rs::{mono::types::MonoDomain, runtime::FerrexRuntime};
+
+use crate::{
+    console, constants::InitFnMono, debug, errors::DynErr, hooks::{NativeHook, invoke_hook},
+    internal_failure, phase::{self, LoaderPhase}, recoverable_failure, utils::exports,
+};
+
+lazy_static! {
+    pub static ref INIT_HOOK: RwLock<NativeHook<InitFnMono>> =
+        RwLock::new(NativeHook::new("mono_jit_init_version", null_mut(), null_mut()));
+}
+
+pub fn detour(name: *const c_char, version: *const c_char) -> *mut MonoDomain {
+    let trampoline = INIT_HOOK.try_read().unwrap_or_else(|e| {
+        internal_failure!("mono_jit_init_version detour failed: {e}");
+    });
+
+    let handles = console::set_handles();
+    let domain = trampoline(name, version);
+
+    if let Err(e) = handles.and_then(|_| detour_inner(&trampoline)) {
+        recoverable_failure!("mono_jit_init_version detour failed: {e}");
+    }
+
+    domain
+}
+
+fn detour_inner(trampoline: &NativeHook<InitFnMono>) -> Result<(), DynErr> {
+    phase::transition(LoaderPhase::RuntimeInitialized)?;
+    invoke_hook::trigger::set_main_thread()?;
+
+    debug!("Detaching hook from mono_jit_init_version")?;
+    trampoline.unhook()?;
+
+    let runtime = crate::runtime!()?;
+
+    debug!("Setting Mono main thread")?;
+    set_main_thread(runtime)?;
+
+    crate::base_assembly::init(runtime)?;
+
+    invoke_hook::hook()?;
+
+    Ok(())
+}
+
+fn set_main_thread(runtime: &FerrexRuntime) -> Result<(), DynErr> {
+    let thread_current = unsafe { std::mem::transmute::<*mut c_void, fn() -> *mut c_void>(exports::resolve(runtime, "mono_thread_current")?) };
+    let thread_set_main = unsafe { std::mem::transmute::<*mut c_void, fn(*mut c_void)>(exports::resolve(runtime, "mono_thread_set_main")?) };
+
+    thread_set_main(thread_current());
+
+    Ok(())
+}
@@ -1,13 +1,17 @@
-use std::{ffi::c_void, sync::RwLock, ptr::null_mut};
+use std::{ffi::{c_char, c_void, CStr}, sync::RwLock, ptr::null_mut};
 
 use lazy_static::lazy_static;
-use unity_rs::{common::method::UnityMethod, il2cpp::types::{Il2CppMethod, Il2CppObject}};
+use unity_rs::{il2cpp::types::{Il2CppMethod, Il2CppObject}, runtime::FerrexRuntime};
 
-use crate::{base_assembly, constants::InvokeFnIl2Cpp, debug, errors::DynErr, hooks::NativeHook, internal_failure, runtime};
+use crate::{base_assembly, constants::InvokeFnIl2Cpp, debug, errors::DynErr, hooks::NativeHook, internal_failure, log, recoverable_failure, runtime, utils::exports};
+
+use super::trigger;
+
+pub const DEFAULT_TRIGGERS: &[&str] = &["Internal_ActiveSceneChanged"];
 
 lazy_static! {
     pub static ref INVOKE_HOOK: RwLock<NativeHook<InvokeFnIl2Cpp>> =
-        RwLock::new(NativeHook::new(null_mut(), null_mut()));
+        RwLock::new(NativeHook::new("il2cpp_runtime_invoke", null_mut(), null_mut()));
 }
 
 pub fn detour(
@@ -16,40 +20,62 @@ pub fn detour(
     params: *mut *mut c_void,
     exc: *mut *mut Il2CppObject,
 ) -> *mut Il2CppObject {
-    detour_inner(method, obj, params, exc).unwrap_or_else(|e| {
+    let trampoline = INVOKE_HOOK.try_read().unwrap_or_else(|e| {
         internal_failure!("il2cpp_runtime_invoke detour failed: {e}");
-    })
-}
+    });
 
-fn detour_inner(
-    method: *mut Il2CppMethod,
-    obj: *mut Il2CppObject,
-    params: *mut *mut c_void,
-    exc: *mut *mut Il2CppObject,
-) -> Result<*mut Il2CppObject, DynErr> {
-    let trampoline = INVOKE_HOOK.try_read()?;
     let result = trampoline(method, obj, params, exc);
 
+    if let Err(e) = detour_inner(&trampoline, method) {
+        recoverable_failure!("il2cpp_runtime_invoke detour failed: {e}");
+    }
+
+    result
+}
+
+fn detour_inner(trampoline: &NativeHook<InvokeFnIl2Cpp>, method: *mut Il2CppMethod) -> Result<(), DynErr> {
     let runtime = runtime!()?;
 
-    let safe_method = UnityMethod::new(method.cast())?;
-    let name = safe_method.get_name(runtime)?;
+    let name = exports::method_name(runtime, method.cast())?;
+
+    let Some(fired) = trigger::check(&name, &|| class_name(runtime, method))? else {
+        return Ok(());
+    };
+
+    log!("Start trigger fired: {fired}");
 
-    if name.contains("Internal_ActiveSceneChanged") {
-        debug!("Detaching hook from il2cpp_runtime_invoke")?;
-        trampoline.unhook()?;
+    debug!("Detaching hook from il2cpp_runtime_invoke")?;
+    trampoline.unhook()?;
 
+    #[cfg(target_os = "android")]
+    {
         debug!("Resetting mono thread")?;
 
         let lib = crate::mono_lib!()?;
         let thread_suspend_reload = lib.exports.mono_melonloader_thread_suspend_reload.as_ref().unwrap();
         thread_suspend_reload();
-        
+
         debug!("Mono thread reset")?;
+    }
 
-        base_assembly::pre_start()?;
-        base_assembly::start()?;
+    base_assembly::pre_start()?;
+    base_assembly::start()?;
+
+    Ok(())
+}
+
+fn class_name(runtime: &FerrexRuntime, method: *mut Il2CppMethod) -> Result<String, DynErr> {
+    let get_class = unsafe { std::mem::transmute::<*mut c_void, fn(*mut Il2CppMethod) -> *mut c_void>(exports::resolve(runtime, "il2cpp_method_get_class")?) };
+    let get_name = unsafe { std::mem::transmute::<*mut c_void, fn(*mut c_void) -> *const c_char>(exports::resolve(runtime, "il2cpp_class_get_name")?) };
+    let get_namespace = unsafe { std::mem::transmute::<*mut c_void, fn(*mut c_void) -> *const c_char>(exports::resolve(runtime, "il2cpp_class_get_namespace")?) };
+
+    let class = get_class(method);
+    if class.is_null() {
+        return Err("Method has no declaring class".into());
     }
 
-    Ok(result)
+    let name = unsafe { CStr::from_ptr(get_name(class)) }.to_string_lossy();
+    let namespace = unsafe { CStr::from_ptr(get_namespace(class)) }.to_string_lossy();
+
+    Ok(trigger::full_class_name(&namespace, &name))
 }
@@ -1,10 +1,12 @@
 mod il2cpp;
+mod mono;
+pub mod trigger;
 
 use crate::{
     debug,
     errors::DynErr,
     runtime,
-    internal_failure,
+    utils::exports,
 };
 use std::ffi::c_void;
 use unity_rs::runtime::RuntimeType;
@@ -15,16 +17,30 @@ pub fn hook() -> Result<(), DynErr> {
     let runtime = runtime!()?;
 
     match runtime.get_type() {
-        RuntimeType::Mono(_) => internal_failure!("Mono is unsupported."),
+        RuntimeType::Mono(_) => {
+            debug!("Attaching hook to mono_runtime_invoke")?;
+
+            let init_function = exports::resolve(runtime, "mono_runtime_invoke")?;
+            let detour = mono::detour as usize;
+
+            trigger::arm(mono::DEFAULT_TRIGGERS)?;
+
+            let mut init_hook = mono::INVOKE_HOOK.try_write()?;
+            *init_hook = NativeHook::new("mono_runtime_invoke", init_function, detour as *mut c_void);
+
+            init_hook.hook()?;
+        }
 
         RuntimeType::Il2Cpp(_) => {
             debug!("Attaching hook to il2cpp_runtime_invoke")?;
 
-            let init_function = runtime.get_export_ptr("il2cpp_runtime_invoke")?;
+            let init_function = exports::resolve(runtime, "il2cpp_runtime_invoke")?;
             let detour = il2cpp::detour as usize;
 
+            trigger::arm(il2cpp::DEFAULT_TRIGGERS)?;
+
             let mut init_hook = il2cpp::INVOKE_HOOK.try_write()?;
-            *init_hook = NativeHook::new(init_function, detour as *mut c_void);
+            *init_hook = NativeHook::new("il2cpp_runtime_invoke", init_function, detour as *mut c_void);
 
             init_hook.hook()?;
         }
@@ -32,3 +48,10 @@ pub fn hook() -> Result<(), DynErr> {
 
     Ok(())
 }
+
+pub fn unhook() -> Result<(), DynErr> {
+    il2cpp::INVOKE_HOOK.try_read()?.unhook()?;
+    mono::INVOKE_HOOK.try_read()?.unhook()?;
+
+    Ok(())
+}
@@ -0,0 +1,74 @@
+use std::{ffi::{c_char, c_void, CStr}, sync::RwLock, ptr::null_mut};
+
+use lazy_static::lazy_static;
+use unity_rs::{mono::types::{MonoMethod, MonoObject}, runtime::FerrexRuntime};
+
+use crate::{base_assembly, constants::InvokeFnMono, debug, errors::DynErr, hooks::NativeHook, internal_failure, log, recoverable_failure, runtime, utils::exports};
+
+use super::trigger;
+
+// older Unity versions never invoke Internal_ActiveSceneChanged on Mono
+pub const DEFAULT_TRIGGERS: &[&str] = &[
+    "Internal_ActiveSceneChanged",
+    "UnityEngine.ISerializationCallbackReceiver.OnAfterSerialize",
+];
+
+lazy_static! {
+    pub static ref INVOKE_HOOK: RwLock<NativeHook<InvokeFnMono>> =
+        RwLock::new(NativeHook::new("mono_runtime_invoke", null_mut(), null_mut()));
+}
+
+pub fn detour(
+    method: *mut MonoMethod,
+    obj: *mut MonoObject,
+    params: *mut *mut c_void,
+    exc: *mut *mut MonoObject,
+) -> *mut MonoObject {
+    let trampoline = INVOKE_HOOK.try_read().unwrap_or_else(|e| {
+        internal_failure!("mono_runtime_invoke detour failed: {e}");
+    });
+
+    let result = trampoline(method, obj, params, exc);
+
+    if let Err(e) = detour_inner(&trampoline, method) {
+        recoverable_failure!("mono_runtime_invoke detour failed: {e}");
+    }
+
+    result
+}
+
+fn detour_inner(trampoline: &NativeHook<InvokeFnMono>, method: *mut MonoMethod) -> Result<(), DynErr> {
+    let runtime = runtime!()?;
+
+    let name = exports::method_name(runtime, method.cast())?;
+
+    let Some(fired) = trigger::check(&name, &|| class_name(runtime, method))? else {
+        return Ok(());
+    };
+
+    log!("Start trigger fired: {fired}");
+
+    debug!("Detaching hook from mono_runtime_invoke")?;
+    trampoline.unhook()?;
+
+    base_assembly::pre_start()?;
+    base_assembly::start()?;
+
+    Ok(())
+}
+
+fn class_name(runtime: &FerrexRuntime, method: *mut MonoMethod) -> Result<String, DynErr> {
+    let get_class = unsafe { std::mem::transmute::<*mut c_void, fn(*mut MonoMethod) -> *mut c_void>(exports::resolve(runtime, "mono_method_get_class")?) };
+    let get_name = unsafe { std::mem::transmute::<*mut c_void, fn(*mut c_void) -> *const c_char>(exports::resolve(runtime, "mono_class_get_name")?) };
+    let get_namespace = unsafe { std::mem::transmute::<*mut c_void, fn(*mut c_void) -> *const c_char>(exports::resolve(runtime, "mono_class_get_namespace")?) };
+
+    let class = get_class(method);
+    if class.is_null() {
+        return Err("Method has no declaring class".into());
+    }
+
+    let name = unsafe { CStr::from_ptr(get_name(class)) }.to_string_lossy();
+    let namespace = unsafe { CStr::from_ptr(get_namespace(class)) }.to_string_lossy();
+
+    Ok(trigger::full_class_name(&namespace, &name))
+}
@@ -0,0 +1,196 @@
+//! Decides which invoke starts MelonLoader.
+//!
+//! Triggers come from the `startTrigger` section of `UserData/Bootstrap.json`, and fall back to
+//! the runtime's defaults. Time based triggers only fire on the thread that initialized the runtime,
+//! or when we attached late, on the game thread once it first invokes something.
+
+use std::{
+    sync::RwLock,
+    thread::{self, ThreadId},
+    time::Instant,
+};
+
+use lazy_static::lazy_static;
+
+use crate::{errors::DynErr, melonenv::config::CONFIG};
+
+#[derive(Debug, Clone)]
+pub enum StartTrigger {
+    /// any method whose name contains this
+    Method(String),
+    /// a method with exactly this name, on a class with exactly this full name
+    QualifiedMethod { class: String, method: String },
+    /// the first invoke after this many milliseconds
+    AfterMs(u64),
+}
+
+impl StartTrigger {
+    pub fn parse(value: &str) -> StartTrigger {
+        match value.rsplit_once("::") {
+            Some((class, method)) => StartTrigger::QualifiedMethod {
+                class: class.to_string(),
+                method: method.to_string(),
+            },
+            None => StartTrigger::Method(value.to_string()),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct TriggerState {
+    triggers: Vec<StartTrigger>,
+    armed_at: Option<Instant>,
+    main_thread: Option<ThreadId>,
+    /// the main thread gets recorded by the first invoke made on the game thread
+    awaiting_main_thread: bool,
+}
+
+lazy_static! {
+    static ref STATE: RwLock<TriggerState> = RwLock::new(TriggerState {
+        triggers: Vec::new(),
+        armed_at: None,
+        main_thread: None,
+        awaiting_main_thread: false,
+    });
+}
+
+/// Remembers the calling thread as the one time based triggers are allowed to fire on.
+pub fn set_main_thread() -> Result<(), DynErr> {
+    let mut state = STATE.try_write()?;
+    state.main_thread = Some(thread::current().id());
+    state.awaiting_main_thread = false;
+
+    Ok(())
+}
+
+/// For when we aren't on the main thread ourselves, the first invoke made on the game thread gets remembered instead.
+/// Time based triggers don't fire until then.
+pub fn await_main_thread() -> Result<(), DynErr> {
+    let mut state = STATE.try_write()?;
+    state.main_thread = None;
+    state.awaiting_main_thread = true;
+
+    Ok(())
+}
+
+/// Unity runs its game loop on a thread called UnityMain on Android.
+#[cfg(target_os = "android")]
+fn is_game_thread() -> bool {
+    let mut name = [0u8; 16];
+    unsafe { libc::prctl(libc::PR_GET_NAME, name.as_mut_ptr()) };
+
+    name.starts_with(b"UnityMain\0")
+}
+
+/// On desktop the game loop runs on the process' initial thread.
+#[cfg(target_os = "linux")]
+fn is_game_thread() -> bool {
+    unsafe { libc::gettid() == libc::getpid() }
+}
+
+#[cfg(not(any(target_os = "android", target_os = "linux")))]
+fn is_game_thread() -> bool {
+    true
+}
+
+fn record_main_thread() {
+    let awaiting = STATE.try_read().is_ok_and(|state| state.awaiting_main_thread);
+    if !awaiting || !is_game_thread() {
+        return;
+    }
+
+    // another invoke holding the lock just means the next one records it
+    if let Ok(mut state) = STATE.try_write() {
+        state.main_thread = Some(thread::current().id());
+        state.awaiting_main_thread = false;
+    }
+}
+
+/// Loads the configured triggers, or `defaults` if there are none, and starts the clock.
+pub fn arm(defaults: &[&str]) -> Result<(), DynErr> {
+    let config = &CONFIG.start_trigger;
+
+    let mut triggers: Vec<StartTrigger> = match &config.methods {
+        Some(methods) => methods.iter().map(|m| StartTrigger::parse(m)).collect(),
+        None => defaults.iter().map(|m| StartTrigger::parse(m)).collect(),
+    };
+
+    if let Some(after_ms) = config.after_ms {
+        triggers.push(StartTrigger::AfterMs(after_ms));
+    }
+
+    let mut state = STATE.try_write()?;
+    state.triggers = triggers;
+    state.armed_at = Some(Instant::now());
+
+    Ok(())
+}
+
+/// Checks the invoked method against every trigger, returning a description of the one that fired.
+///
+/// `class_name` is only called if a class qualified trigger matches the method name.
+pub fn check(
+    method_name: &str,
+    class_name: &dyn Fn() -> Result<String, DynErr>,
+) -> Result<Option<String>, DynErr> {
+    record_main_thread();
+
+    let state = STATE.try_read()?;
+
+    let elapsed_ms = state
+        .armed_at
+        .map(|armed_at| armed_at.elapsed().as_millis() as u64)
+        .unwrap_or_default();
+    let on_main_thread = match state.main_thread {
+        Some(id) => id == thread::current().id(),
+        None => !state.awaiting_main_thread,
+    };
+
+    let mut cached_class: Option<String> = None;
+
+    for trigger in state.triggers.iter() {
+        match trigger {
+            StartTrigger::Method(name) => {
+                if method_name.contains(name.as_str()) {
+                    return Ok(Some(format!("method {name}")));
+                }
+            }
+
+            StartTrigger::QualifiedMethod { class, method } => {
+                if method_name != method {
+                    continue;
+                }
+
+                if cached_class.is_none() {
+                    cached_class = Some(class_name()?);
+                }
+
+                if cached_class.as_deref() == Some(class.as_str()) {
+                    return Ok(Some(format!("method {class}::{method}")));
+                }
+            }
+
+            StartTrigger::AfterMs(after_ms) => {
+                if on_main_thread && elapsed_ms >= *after_ms {
+                    return Ok(Some(format!("first invoke after {after_ms}ms")));
+                }
+            }
+        }
+    }
+
+    if let Some(timeout_ms) = CONFIG.start_trigger.timeout_ms {
+        if on_main_thread && elapsed_ms >= timeout_ms {
+            return Ok(Some(format!("timeout after {timeout_ms}ms")));
+        }
+    }
+
+    Ok(None)
+}
+
+/// Joins a namespace and class name the way triggers spell them.
+pub fn full_class_name(namespace: &str, name: &str) -> String {
+    match namespace.is_empty() {
+        true => name.to_string(),
+        false => format!("{namespace}.{name}"),
+    }
+}
@@ -1,31 +1,78 @@
-use crate::errors::DynErr;
+use crate::{errors::DynErr, utils};
 use std::marker::PhantomData;
 use std::ops::Deref;
 use std::ffi::c_void;
 use std::ptr::null_mut;
 
+pub mod chain;
+pub mod diagnostics;
 pub mod functions;
 pub mod init_hook;
 pub mod invoke_hook;
+pub mod patch;
+pub mod plt;
+pub mod prologue;
+pub mod registry;
+pub mod stubs;
+pub mod transaction;
+
+/// Where a hook gets placed.
+#[derive(Debug, Clone, Copy)]
+pub enum HookBackend<'a> {
+    /// A Dobby inline hook on the function at this address, every caller gets redirected.
+    Inline(usize),
+    /// Rewrites `module`'s GOT slots for `symbol`, only calls made from that module get redirected.
+    Plt { module: &'a str, symbol: &'a str },
+    /// An inline hook on `symbol`, looked up in `module`'s symbol tables on disk, so it doesn't need to be exported.
+    Symbol { module: &'a str, symbol: &'a str },
+}
+
+/// Attaches `detour` with the chosen backend and records it, returning the hook id and trampoline.
+pub fn attach(name: &str, owner: &str, backend: HookBackend, detour: usize) -> Result<(registry::HookId, usize), DynErr> {
+    match backend {
+        HookBackend::Inline(target) => registry::attach(name, owner, target, detour),
+        HookBackend::Plt { module, symbol } => registry::attach_plt(name, owner, module, symbol, detour),
+        HookBackend::Symbol { module, symbol } => {
+            registry::attach(name, owner, utils::symbols::resolve(module, symbol)?, detour)
+        }
+    }
+}
+
+/// Detaches every hook in the registry, failures are logged and skipped.
+pub fn detach_all() {
+    registry::detach_all();
+}
 
 #[derive(Debug)]
 pub struct NativeHook<T> {
+    pub name: &'static str,
+    pub owner: &'static str,
     pub target: *mut c_void,
     pub trampoline: *mut c_void,
     pub detour: *mut c_void,
+    pub id: Option<registry::HookId>,
     pd: PhantomData<T>,
 }
 
 impl<T> NativeHook<T> {
-    pub fn new(target: *mut c_void, detour: *mut c_void) -> Self {
+    pub fn new(name: &'static str, target: *mut c_void, detour: *mut c_void) -> Self {
         Self {
+            name,
+            owner: registry::OWNER_BOOTSTRAP,
             target,
             trampoline: null_mut(),
             detour,
+            id: None,
             pd: PhantomData,
         }
     }
 
+    /// Records the hook under `owner` instead of the Bootstrap.
+    pub fn with_owner(mut self, owner: &'static str) -> Self {
+        self.owner = owner;
+        self
+    }
+
     pub fn is_hooked(&self) -> bool {
         !self.target.is_null() && !self.trampoline.is_null()
     }
@@ -35,20 +82,23 @@ impl<T> NativeHook<T> {
             return Ok(());
         }
 
-        let trampoline = functions::hook(self.target as usize, self.detour as usize)?;
+        let (id, trampoline) = registry::attach(
+            self.name,
+            self.owner,
+            self.target as usize,
+            self.detour as usize,
+        )?;
 
+        self.id = Some(id);
         self.trampoline = trampoline as *mut c_void;
         Ok(())
     }
 
     pub fn unhook(&self) -> Result<(), DynErr> {
-        if !self.is_hooked() {
-            return Ok(());
+        match self.id {
+            Some(id) if self.is_hooked() => registry::detach(id),
+            _ => Ok(()),
         }
-
-        functions::unhook(self.target as usize)?;
-
-        Ok(())
     }
 }
 
@@ -0,0 +1,214 @@
+//! Raw byte patches in code pages.
+//!
+//! Every patch keeps the bytes it replaced and is recorded in the hook registry, so detaching it,
+//! or detaching everything on shutdown, puts the original code back.
+
+use std::{
+    collections::BTreeMap,
+    ffi::c_void,
+    sync::Mutex,
+};
+
+use lazy_static::lazy_static;
+
+use crate::{
+    debug,
+    errors::{patcherr::PatchError, DynErr},
+    hooks::registry::{self, HookId, HookKind},
+    utils::maps,
+};
+
+#[cfg(any(target_arch = "x86_64", target_arch = "x86"))]
+const NOP: &[u8] = &[0x90];
+#[cfg(target_arch = "aarch64")]
+const NOP: &[u8] = &0xD503_201Fu32.to_le_bytes();
+/// the ARM mode NOP, Thumb code gets `THUMB_NOP` instead
+#[cfg(target_arch = "arm")]
+const NOP: &[u8] = &0xE320_F000u32.to_le_bytes();
+#[cfg(target_arch = "arm")]
+const THUMB_NOP: &[u8] = &0xBF00u16.to_le_bytes();
+
+#[derive(Debug, Clone)]
+struct Patch {
+    original: Vec<u8>,
+}
+
+lazy_static! {
+    /// keyed by address, ordered so overlaps are cheap to find
+    static ref PATCHES: Mutex<BTreeMap<usize, Patch>> = Mutex::new(BTreeMap::new());
+}
+
+/// Overwrites the code at `address` with `bytes`.
+pub fn write_bytes(owner: &str, address: usize, bytes: &[u8]) -> Result<HookId, DynErr> {
+    apply(address, bytes)?;
+
+    let name = format!("patch {:#x}+{}", address, bytes.len());
+    let (id, _) = registry::record(&name, owner, HookKind::Patch, address, 0, 0)?;
+
+    Ok(id)
+}
+
+/// Fills `len` bytes at `address` with the platform's NOP instruction.
+/// On arm, an address with the thumb bit set is treated as Thumb code, same as function pointers are.
+pub fn nop(owner: &str, address: usize, len: usize) -> Result<HookId, DynErr> {
+    let (address, nop) = nop_at(address);
+
+    if !len.is_multiple_of(nop.len()) {
+        return Err(PatchError::MisalignedNop(len, nop.len()).into());
+    }
+
+    write_bytes(owner, address, &nop.repeat(len / nop.len()))
+}
+
+/// The address the code starts at, and the NOP for its instruction set.
+fn nop_at(address: usize) -> (usize, &'static [u8]) {
+    #[cfg(target_arch = "arm")]
+    if address & 1 == 1 {
+        return (address & !1, THUMB_NOP);
+    }
+
+    (address, NOP)
+}
+
+/// Writes a pointer sized value at `address`, for vtables, GOT slots and the like.
+pub fn write_pointer(owner: &str, address: usize, value: usize) -> Result<HookId, DynErr> {
+    write_bytes(owner, address, &value.to_ne_bytes())
+}
+
+fn apply(address: usize, bytes: &[u8]) -> Result<(), DynErr> {
+    if address == 0 {
+        return Err(PatchError::Nullpointer.into());
+    }
+
+    if bytes.is_empty() {
+        return Err(PatchError::Empty(address).into());
+    }
+
+    let mut patches = PATCHES.lock().map_err(|_| PatchError::Poisoned)?;
+
+    let end = address + bytes.len();
+    let overlaps = patches
+        .range(..end)
+        .next_back()
+        .is_some_and(|(start, patch)| start + patch.original.len() > address);
+
+    if overlaps {
+        return Err(PatchError::Overlap(address).into());
+    }
+
+    let original = unsafe { std::slice::from_raw_parts(address as *const u8, bytes.len()) }.to_vec();
+    unsafe { write_code(address, bytes)? };
+
+    patches.insert(address, Patch { original });
+
+    debug!("Patched {} byte(s) at {:#x}", bytes.len(), address)?;
+
+    Ok(())
+}
+
+/// Puts the original bytes back. Called by the registry when a patch is detached.
+pub fn restore(address: usize) -> Result<(), DynErr> {
+    let mut patches = PATCHES.lock().map_err(|_| PatchError::Poisoned)?;
+
+    let patch = patches.get(&address).ok_or(PatchError::NotPatched(address))?;
+    unsafe { write_code(address, &patch.original)? };
+
+    patches.remove(&address);
+
+    debug!("Restored patch at {:#x}", address)?;
+
+    Ok(())
+}
+
+/// Adds write access to each page, copies `bytes` over, then puts the protection back the way the maps say it was.
+/// Pages are never made executable if they weren't already.
+pub(crate) unsafe fn write_code(address: usize, bytes: &[u8]) -> Result<(), DynErr> {
+    let page_size = libc::sysconf(libc::_SC_PAGESIZE) as usize;
+    let page_start = address & !(page_size - 1);
+    let page_len = (address + bytes.len() - page_start).div_ceil(page_size) * page_size;
+
+    let maps = maps::read()?;
+    let mut protections = Vec::new();
+    let mut page = page_start;
+    while page < page_start + page_len {
+        let entry = maps.iter().find(|m| m.contains(page)).ok_or(PatchError::Unmapped(page))?;
+
+        let mut prot = libc::PROT_NONE;
+        if entry.perms.read {
+            prot |= libc::PROT_READ;
+        }
+        if entry.perms.write {
+            prot |= libc::PROT_WRITE;
+        }
+        if entry.perms.execute {
+            prot |= libc::PROT_EXEC;
+        }
+
+        protections.push((page, prot));
+        page += page_size;
+    }
+
+    for (i, (page, prot)) in protections.iter().enumerate() {
+        if libc::mprotect(*page as *mut c_void, page_size, prot | libc::PROT_WRITE) != 0 {
+            let error = PatchError::Protect(*page, std::io::Error::last_os_error().to_string());
+
+            for (page, prot) in protections[..i].iter() {
+                libc::mprotect(*page as *mut c_void, page_size, *prot);
+            }
+
+            return Err(error.into());
+        }
+    }
+
+    std::ptr::copy_nonoverlapping(bytes.as_ptr(), address as *mut u8, bytes.len());
+    flush_icache(address, bytes.len());
+
+    for (page, prot) in protections {
+        if libc::mprotect(page as *mut c_void, page_size, prot) != 0 {
+            return Err(PatchError::Protect(page, std::io::Error::last_os_error().to_string()).into());
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(any(target_arch = "arm", target_arch = "aarch64"))]
+pub fn flush_icache(start: usize, len: usize) {
+    extern "C" {
+        fn __clear_cache(start: *mut c_void, end: *mut c_void);
+    }
+
+    unsafe { __clear_cache(start as *mut c_void, (start + len) as *mut c_void) };
+}
+
+/// x86 keeps the instruction cache coherent by itself.
+#[cfg(not(any(target_arch = "arm", target_arch = "aarch64")))]
+pub fn flush_icache(_start: usize, _len: usize) {}
+
+/// Returns the patch's hook id, or 0 on failure.
+///
+/// # Safety
+/// `bytes` must point to `len` readable bytes.
+pub unsafe extern "C" fn patch_write(address: *mut c_void, bytes: *const u8, len: usize) -> HookId {
+    if bytes.is_null() {
+        return 0;
+    }
+
+    let bytes = std::slice::from_raw_parts(bytes, len);
+    report(write_bytes(registry::OWNER_MANAGED, address as usize, bytes))
+}
+
+pub extern "C" fn patch_nop(address: *mut c_void, len: usize) -> HookId {
+    report(nop(registry::OWNER_MANAGED, address as usize, len))
+}
+
+pub extern "C" fn patch_write_pointer(address: *mut c_void, value: *mut c_void) -> HookId {
+    report(write_pointer(registry::OWNER_MANAGED, address as usize, value as usize))
+}
+
+fn report(result: Result<HookId, DynErr>) -> HookId {
+    result.unwrap_or_else(|e| {
+        let _ = crate::error!("Failed to patch memory: {}", e.to_string());
+        0
+    })
+}
@@ -0,0 +1,330 @@
+//! Import table hooks.
+//!
+//! Instead of patching the target function, this rewrites the GOT slots one module uses to call
+//! an imported symbol, so only calls made from that module are redirected. Handy for catching
+//! libc or libunity calls made by `libil2cpp.so` without touching anyone else.
+//!
+//! Only the regular relocation tables are read, Android's packed relocations only ever hold
+//! relative relocations, which never reference a symbol.
+//!
+//! With lazy binding a slot still holds the PLT stub that resolves it on the first call, so the
+//! original import is taken from an already bound slot, or from the dynamic linker if there is none.
+
+use std::{
+    collections::HashMap,
+    ffi::{c_char, c_int, c_void, CStr, CString},
+    path::Path,
+    sync::Mutex,
+};
+
+use lazy_static::lazy_static;
+
+use crate::{
+    debug,
+    errors::{hookerr::HookError, DynErr},
+    hooks::patch,
+    utils::elf,
+};
+
+#[derive(Debug, Clone)]
+struct PltHook {
+    /// every slot with the value it held before, which isn't the same for bound and unbound slots
+    slots: Vec<(usize, usize)>,
+}
+
+lazy_static! {
+    /// keyed by the first slot, which is also what the registry records as the target
+    static ref PLT_HOOKS: Mutex<HashMap<usize, PltHook>> = Mutex::new(HashMap::new());
+}
+
+#[derive(Debug, Clone)]
+struct LoadedModule {
+    path: String,
+    base: usize,
+    dynamic: usize,
+    /// the address range covered by the module's loadable segments
+    start: usize,
+    end: usize,
+}
+
+impl LoadedModule {
+    fn contains(&self, address: usize) -> bool {
+        (self.start..self.end).contains(&address)
+    }
+}
+
+/// Points every GOT slot `module` has for `symbol` at `detour`.
+/// Returns the first slot, which identifies the hook, and the address the slots held before.
+pub fn hook(module: &str, symbol: &str, detour: usize) -> Result<(usize, usize), DynErr> {
+    if detour == 0 {
+        return Err(HookError::Nullpointer("detour".to_string()).into());
+    }
+
+    let loaded = find_module(module).ok_or_else(|| HookError::Failed(format!("{symbol}, {module} is not loaded")))?;
+    let slots: Vec<(usize, usize)> = unsafe { find_slots(&loaded, symbol) }
+        .into_iter()
+        .map(|slot| (slot, unsafe { *(slot as *const usize) }))
+        .collect();
+
+    let first = slots
+        .first()
+        .map(|(slot, _)| *slot)
+        .ok_or_else(|| HookError::Failed(format!("{symbol}, {module} does not import it")))?;
+
+    let mut hooks = PLT_HOOKS.lock().map_err(|_| HookError::Failed(symbol.to_string()))?;
+    if hooks.contains_key(&first) {
+        return Err(HookError::Failed(format!("{symbol} in {module}, it is already hooked")).into());
+    }
+
+    // an unbound slot points back into the module's own PLT
+    let original = slots
+        .iter()
+        .map(|(_, value)| *value)
+        .find(|value| *value != 0 && !loaded.contains(*value))
+        .or_else(|| resolve_import(&loaded, symbol))
+        .ok_or_else(|| HookError::Failed(format!("{symbol} in {module}, it can't be resolved")))?;
+
+    for (i, (slot, _)) in slots.iter().enumerate() {
+        if let Err(e) = unsafe { patch::write_code(*slot, &detour.to_ne_bytes()) } {
+            restore(&slots[..i]);
+            return Err(e);
+        }
+    }
+
+    let _ = debug!("Redirected {} GOT slot(s) of {} in {} to {:#x}", slots.len(), symbol, module, detour);
+
+    hooks.insert(first, PltHook { slots });
+
+    Ok((first, original))
+}
+
+/// Puts the value each slot held before back into every slot of the hook identified by `first_slot`.
+pub fn unhook(first_slot: usize) -> Result<(), DynErr> {
+    let mut hooks = PLT_HOOKS.lock().map_err(|_| HookError::Failed(format!("{first_slot:#x}")))?;
+
+    let hook = hooks
+        .get(&first_slot)
+        .ok_or_else(|| HookError::Failed(format!("{first_slot:#x}, it is not a GOT hook")))?;
+
+    for (slot, previous) in hook.slots.iter() {
+        unsafe { patch::write_code(*slot, &previous.to_ne_bytes())? };
+    }
+
+    hooks.remove(&first_slot);
+
+    Ok(())
+}
+
+fn restore(slots: &[(usize, usize)]) {
+    for (slot, previous) in slots {
+        let _ = unsafe { patch::write_code(*slot, &previous.to_ne_bytes()) };
+    }
+}
+
+/// Asks the dynamic linker what `symbol` binds to, first in the global scope like the binding itself,
+/// then in the module's own dependencies for anything loaded locally.
+fn resolve_import(module: &LoadedModule, symbol: &str) -> Option<usize> {
+    let symbol = CString::new(symbol).ok()?;
+    let path = CString::new(module.path.as_str()).ok()?;
+
+    unsafe {
+        let address = libc::dlsym(libc::RTLD_DEFAULT, symbol.as_ptr());
+        if !address.is_null() {
+            return Some(address as usize);
+        }
+
+        let handle = libc::dlopen(path.as_ptr(), libc::RTLD_LAZY | libc::RTLD_NOLOAD);
+        if handle.is_null() {
+            return None;
+        }
+
+        let address = libc::dlsym(handle, symbol.as_ptr());
+        libc::dlclose(handle);
+
+        (!address.is_null()).then_some(address as usize)
+    }
+}
+
+/// Finds a loaded module by file name. The main executable reports an empty name, so it's matched by its path instead.
+fn find_module(module: &str) -> Option<LoadedModule> {
+    struct Search<'a> {
+        module: &'a str,
+        exe: Option<String>,
+        found: Option<LoadedModule>,
+    }
+
+    unsafe extern "C" fn callback(info: *mut libc::dl_phdr_info, _size: usize, data: *mut c_void) -> c_int {
+        let search = &mut *(data as *mut Search);
+        let info = &*info;
+
+        let name = match info.dlpi_name.is_null() {
+            true => String::new(),
+            false => CStr::from_ptr(info.dlpi_name).to_string_lossy().into_owned(),
+        };
+
+        let name = match name.is_empty() {
+            true => search.exe.clone().unwrap_or_default(),
+            false => name,
+        };
+
+        if Path::new(&name).file_name().and_then(|n| n.to_str()) != Some(search.module) {
+            return 0;
+        }
+
+        let phdrs = std::slice::from_raw_parts(info.dlpi_phdr as *const elf::Phdr, info.dlpi_phnum as usize);
+        let Some(dynamic) = phdrs.iter().find(|p| p.p_type == libc::PT_DYNAMIC) else { return 0 };
+
+        let base = info.dlpi_addr as usize;
+        let loads = phdrs.iter().filter(|p| p.p_type == libc::PT_LOAD);
+        let start = loads.clone().map(|p| p.p_vaddr as usize).min().unwrap_or(0);
+        let end = loads.map(|p| (p.p_vaddr + p.p_memsz) as usize).max().unwrap_or(0);
+
+        search.found = Some(LoadedModule {
+            path: name,
+            base,
+            dynamic: base + dynamic.p_vaddr as usize,
+            start: base + start,
+            end: base + end,
+        });
+
+        1
+    }
+
+    let mut search = Search {
+        module,
+        exe: std::env::current_exe().ok().map(|p| p.to_string_lossy().into_owned()),
+        found: None,
+    };
+
+    unsafe { libc::dl_iterate_phdr(Some(callback), &mut search as *mut Search as *mut c_void) };
+
+    search.found
+}
+
+/// Every GOT slot in `module` whose relocation references `symbol`.
+unsafe fn find_slots(module: &LoadedModule, symbol: &str) -> Vec<usize> {
+    // glibc relocates the dynamic section in place, bionic leaves the raw vaddrs
+    let address = |value: usize| match value < module.base {
+        true => module.base + value,
+        false => value,
+    };
+
+    let (mut strtab, mut symtab) = (0, 0);
+    let (mut rela, mut rela_size, mut rel, mut rel_size) = (0, 0, 0, 0);
+    let (mut jmprel, mut jmprel_size, mut jmprel_is_rela) = (0, 0, cfg!(target_pointer_width = "64"));
+
+    let mut entry = module.dynamic as *const elf::Dyn;
+    while (*entry).d_tag as isize != elf::DT_NULL {
+        let value = (*entry).d_val as usize;
+
+        match (*entry).d_tag as isize {
+            elf::DT_STRTAB => strtab = address(value),
+            elf::DT_SYMTAB => symtab = address(value),
+            elf::DT_RELA => rela = address(value),
+            elf::DT_RELASZ => rela_size = value,
+            elf::DT_REL => rel = address(value),
+            elf::DT_RELSZ => rel_size = value,
+            elf::DT_JMPREL => jmprel = address(value),
+            elf::DT_PLTRELSZ => jmprel_size = value,
+            elf::DT_PLTREL => jmprel_is_rela = value as isize == elf::DT_RELA,
+            _ => {}
+        }
+
+        entry = entry.add(1);
+    }
+
+    if strtab == 0 || symtab == 0 {
+        return Vec::new();
+    }
+
+    let matches = |info| {
+        let sym = &*(symtab as *const elf::Sym).add(elf::r_sym(info));
+        elf::GOT_RELOCATIONS.contains(&elf::r_type(info))
+            && sym.st_name != 0
+            && CStr::from_ptr((strtab + sym.st_name as usize) as *const c_char).to_bytes() == symbol.as_bytes()
+    };
+
+    let mut slots = Vec::new();
+    let mut scan_rela = |table: usize, size: usize| {
+        if table == 0 {
+            return;
+        }
+        let entries = std::slice::from_raw_parts(table as *const elf::Rela, size / std::mem::size_of::<elf::Rela>());
+        slots.extend(entries.iter().filter(|r| matches(r.r_info)).map(|r| module.base + r.r_offset as usize));
+    };
+
+    scan_rela(rela, rela_size);
+    if jmprel_is_rela {
+        scan_rela(jmprel, jmprel_size);
+    }
+
+    let mut scan_rel = |table: usize, size: usize| {
+        if table == 0 {
+            return;
+        }
+        let entries = std::slice::from_raw_parts(table as *const elf::Rel, size / std::mem::size_of::<elf::Rel>());
+        slots.extend(entries.iter().filter(|r| matches(r.r_info)).map(|r| module.base + r.r_offset as usize));
+    };
+
+    scan_rel(rel, rel_size);
+    if !jmprel_is_rela {
+        scan_rel(jmprel, jmprel_size);
+    }
+
+    slots.sort_unstable();
+    slots.dedup();
+    slots
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::process::Command;
+
+    const LIBRARY: &str = "libmelon_plt_test.so";
+    const SOURCE: &str = "#include <unistd.h>\nint plt_test_pid(void) { return getpid(); }\n";
+
+    extern "C" fn fake_getpid() -> libc::pid_t {
+        4242
+    }
+
+    #[test]
+    fn redirects_and_restores_imports() {
+        let dir = std::env::temp_dir().join(format!("melonloader-plt-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let source = dir.join("plt_test.c");
+        let library = dir.join(LIBRARY);
+        std::fs::write(&source, SOURCE).unwrap();
+
+        // lazy binding, so the slot still holds the PLT stub when it gets hooked
+        let status = Command::new(std::env::var("CC").unwrap_or_else(|_| "cc".to_string()))
+            .args(["-shared", "-fPIC", "-fno-builtin", "-Wl,-z,lazy", "-o"])
+            .arg(&library)
+            .arg(&source)
+            .status()
+            .expect("a C compiler is needed to build the test library");
+        assert!(status.success());
+
+        let path = CString::new(library.to_str().unwrap()).unwrap();
+        let handle = unsafe { libc::dlopen(path.as_ptr(), libc::RTLD_LAZY) };
+        assert!(!handle.is_null());
+
+        let name = CString::new("plt_test_pid").unwrap();
+        let call: extern "C" fn() -> libc::pid_t = unsafe { std::mem::transmute(libc::dlsym(handle, name.as_ptr())) };
+        let pid = unsafe { libc::getpid() };
+
+        let (slot, original) = hook(LIBRARY, "getpid", fake_getpid as *const () as usize).unwrap();
+        let original: extern "C" fn() -> libc::pid_t = unsafe { std::mem::transmute(original) };
+
+        assert_eq!(call(), 4242);
+        assert_eq!(original(), pid);
+        assert_eq!(unsafe { libc::getpid() }, pid);
+
+        unhook(slot).unwrap();
+        assert_eq!(call(), pid);
+
+        unsafe { libc::dlclose(handle) };
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,67 @@
+//! Recognizes function prologues that already start with a jump, which usually means something else hooked them first.
+
+/// How many bytes `is_branch` looks at.
+pub const PROLOGUE_LEN: usize = 16;
+
+#[cfg(any(target_arch = "x86_64", target_arch = "x86"))]
+pub fn is_branch(code: &[u8]) -> bool {
+    match code {
+        // jmp rel32, jmp rel8
+        [0xE9, ..] | [0xEB, ..] => true,
+        // jmp [rip+disp32] on x86_64, jmp [abs32] on x86
+        [0xFF, 0x25, ..] => true,
+        // push imm32; ret
+        [0x68, _, _, _, _, 0xC3, ..] => true,
+        // movabs r64, imm64; jmp r64
+        [0x48 | 0x49, 0xB8..=0xBF, _, _, _, _, _, _, _, _, 0xFF, 0xE0..=0xE7, ..] => true,
+        [0x48 | 0x49, 0xB8..=0xBF, _, _, _, _, _, _, _, _, 0x41, 0xFF, 0xE0..=0xE7, ..] => true,
+        _ => false,
+    }
+}
+
+#[cfg(target_arch = "aarch64")]
+pub fn is_branch(code: &[u8]) -> bool {
+    let insns: Vec<u32> = code
+        .chunks_exact(4)
+        .map(|c| u32::from_le_bytes([c[0], c[1], c[2], c[3]]))
+        .collect();
+
+    let is_br = |insn: u32| insn & 0xFFFF_FC1F == 0xD61F_0000;
+
+    match insns.as_slice() {
+        // b imm26
+        [b, ..] if b & 0xFC00_0000 == 0x1400_0000 => true,
+        // ldr xN, #literal; br xN
+        [ldr, br, ..] if ldr & 0xFF00_0000 == 0x5800_0000 && is_br(*br) => true,
+        // adrp xN, page; (add|ldr) xN, ...; br xN
+        [adrp, _, br, ..] if adrp & 0x9F00_0000 == 0x9000_0000 && is_br(*br) => true,
+        _ => false,
+    }
+}
+
+#[cfg(target_arch = "arm")]
+pub fn is_branch(code: &[u8]) -> bool {
+    let Some(first) = code.get(..4) else {
+        return false;
+    };
+    let insn = u32::from_le_bytes([first[0], first[1], first[2], first[3]]);
+
+    // unconditional b, or ldr pc, [pc, #imm]
+    insn & 0xFF00_0000 == 0xEA00_0000 || insn & 0xFF7F_F000 == 0xE51F_F000
+}
+
+/// The thumb flavour of `is_branch`, for targets with the low bit set.
+#[cfg(target_arch = "arm")]
+pub fn is_thumb_branch(code: &[u8]) -> bool {
+    let halves: Vec<u16> = code.chunks_exact(2).map(|c| u16::from_le_bytes([c[0], c[1]])).collect();
+
+    match halves.as_slice() {
+        // ldr.w pc, [pc, #imm]
+        [0xF8DF, second, ..] if second & 0xF000 == 0xF000 => true,
+        // b.w imm24
+        [first, second, ..] if first & 0xF800 == 0xF000 && second & 0xD000 == 0x9000 => true,
+        // b imm11
+        [first, ..] if first & 0xF800 == 0xE000 => true,
+        _ => false,
+    }
+}
@@ -0,0 +1,304 @@
+//! Keeps track of every hook attached through the Bootstrap, whether it came from us or from managed code.
+//!
+//! Records are never removed, a detached hook just changes state, so ids stay stable and
+//! the pointers handed out through `HostExports` stay valid for the lifetime of the process.
+
+use std::{
+    ffi::{c_char, c_void, CStr, CString},
+    sync::{
+        atomic::{AtomicU32, Ordering},
+        RwLock,
+    },
+};
+
+use chrono::{DateTime, Local};
+use lazy_static::lazy_static;
+
+use crate::{
+    debug,
+    errors::{hookerr::HookError, DynErr},
+    hooks::{chain, functions, patch, plt},
+};
+
+pub type HookId = u32;
+
+/// Owner of hooks the Bootstrap attaches for itself.
+pub const OWNER_BOOTSTRAP: &str = "Bootstrap";
+/// Owner of hooks requested through `BootstrapInterop`.
+pub const OWNER_MANAGED: &str = "Managed";
+/// Owner of hooks requested by native mods through the C ABI.
+pub const OWNER_NATIVE: &str = "Native";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum HookState {
+    Attached,
+    Detached,
+}
+
+/// How a hook was placed on its target.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum HookKind {
+    /// A Dobby hook owning the target.
+    Inline,
+    /// One link in a `chain`, sharing the target with other detours.
+    Chained,
+    /// Raw bytes written by `patch`, there is no detour or trampoline.
+    Patch,
+    /// GOT slots rewritten by `plt`, the target is the first slot and the trampoline the original import.
+    Plt,
+}
+
+#[derive(Debug, Clone)]
+pub struct HookRecord {
+    pub id: HookId,
+    pub name: String,
+    pub owner: String,
+    pub kind: HookKind,
+    pub target: usize,
+    pub detour: usize,
+    pub trampoline: usize,
+    pub attached_at: DateTime<Local>,
+    pub state: HookState,
+
+    c_name: CString,
+    c_owner: CString,
+}
+
+/// The C view of a `HookRecord`, the strings are owned by the registry.
+#[repr(C)]
+#[derive(Debug)]
+pub struct HookInfo {
+    pub id: HookId,
+    pub state: HookState,
+    pub kind: HookKind,
+    pub target: *mut c_void,
+    pub detour: *mut c_void,
+    pub trampoline: *mut c_void,
+    /// unix time in milliseconds
+    pub attached_at: i64,
+    pub name: *const c_char,
+    pub owner: *const c_char,
+}
+
+lazy_static! {
+    static ref REGISTRY: RwLock<Vec<HookRecord>> = RwLock::new(Vec::new());
+}
+
+static NEXT_ID: AtomicU32 = AtomicU32::new(1);
+
+/// Hooks `target` and records it, returning the new hook's id and trampoline.
+pub fn attach(name: &str, owner: &str, target: usize, detour: usize) -> Result<(HookId, usize), DynErr> {
+    let trampoline = functions::hook(target, detour)?;
+    record(name, owner, HookKind::Inline, target, detour, trampoline)
+}
+
+/// Adds `detour` to the chain on `target`, see `hooks::chain`.
+pub fn attach_chained(name: &str, owner: &str, target: usize, detour: usize) -> Result<(HookId, usize), DynErr> {
+    let trampoline = chain::attach(target, detour)?;
+    record(name, owner, HookKind::Chained, target, detour, trampoline)
+}
+
+/// Like `attach_chained`, but puts `detour` at `index` in the chain, see `chain::attach_at`.
+pub fn attach_chained_at(
+    name: &str,
+    owner: &str,
+    target: usize,
+    detour: usize,
+    index: usize,
+) -> Result<(HookId, usize), DynErr> {
+    let trampoline = chain::attach_at(target, detour, index)?;
+    record(name, owner, HookKind::Chained, target, detour, trampoline)
+}
+
+/// Redirects `module`'s imports of `symbol` to `detour`, see `hooks::plt`.
+pub fn attach_plt(name: &str, owner: &str, module: &str, symbol: &str, detour: usize) -> Result<(HookId, usize), DynErr> {
+    let (slot, original) = plt::hook(module, symbol, detour)?;
+    record(name, owner, HookKind::Plt, slot, detour, original)
+}
+
+pub(crate) fn record(
+    name: &str,
+    owner: &str,
+    kind: HookKind,
+    target: usize,
+    detour: usize,
+    trampoline: usize,
+) -> Result<(HookId, usize), DynErr> {
+    let id = NEXT_ID.fetch_add(1, Ordering::SeqCst);
+
+    REGISTRY.write().map_err(|_| HookError::Failed(name.to_string()))?.push(HookRecord {
+        id,
+        name: name.to_string(),
+        owner: owner.to_string(),
+        kind,
+        target,
+        detour,
+        trampoline,
+        attached_at: Local::now(),
+        state: HookState::Attached,
+        c_name: CString::new(name).unwrap_or_default(),
+        c_owner: CString::new(owner).unwrap_or_default(),
+    });
+
+    debug!("Attached hook #{} {} ({}) at {:#x}", id, name, owner, target)?;
+
+    Ok((id, trampoline))
+}
+
+/// Detaches a single hook. Detaching something that is already detached does nothing.
+pub fn detach(id: HookId) -> Result<(), DynErr> {
+    // claimed under the lock, but unhooked outside of it, so a detach never blocks readers of the registry
+    let record = {
+        let mut registry = REGISTRY.write().map_err(|_| HookError::Failed(format!("hook #{id}")))?;
+
+        let record = registry
+            .iter_mut()
+            .find(|r| r.id == id)
+            .ok_or_else(|| format!("No hook with id {id}"))?;
+
+        if record.state == HookState::Detached {
+            return Ok(());
+        }
+
+        record.state = HookState::Detached;
+        record.clone()
+    };
+
+    let result = match record.kind {
+        HookKind::Inline => functions::unhook(record.target),
+        HookKind::Chained => chain::detach(record.target, record.detour),
+        HookKind::Patch => patch::restore(record.target),
+        HookKind::Plt => plt::unhook(record.target),
+    };
+
+    if let Err(e) = result {
+        set_state(id, HookState::Attached);
+        return Err(e);
+    }
+
+    debug!("Detached hook #{} {} ({})", record.id, record.name, record.owner)?;
+
+    Ok(())
+}
+
+fn set_state(id: HookId, state: HookState) {
+    if let Ok(mut registry) = REGISTRY.write() {
+        if let Some(record) = registry.iter_mut().find(|r| r.id == id) {
+            record.state = state;
+        }
+    }
+}
+
+/// Detaches every attached hook, newest first so stacked hooks unwind in order.
+pub fn detach_all() {
+    let attached: Vec<HookId> = list()
+        .into_iter()
+        .rev()
+        .filter(|r| r.state == HookState::Attached)
+        .map(|r| r.id)
+        .collect();
+
+    for id in attached {
+        if let Err(e) = detach(id) {
+            let _ = debug!("Failed to detach hook #{}: {}", id, e.to_string());
+        }
+    }
+}
+
+pub fn list() -> Vec<HookRecord> {
+    REGISTRY.read().map(|r| r.clone()).unwrap_or_default()
+}
+
+pub fn get(id: HookId) -> Option<HookRecord> {
+    REGISTRY.read().ok()?.iter().find(|r| r.id == id).cloned()
+}
+
+/// Finds the attached hook that was placed on `address`, or whose trampoline is `address`.
+/// A non-zero `detour` narrows it down when several detours share a chained target.
+/// Patches are never matched, they have no detour and are only detached by id.
+pub fn find_attached(address: usize, detour: usize) -> Option<HookRecord> {
+    REGISTRY
+        .read()
+        .ok()?
+        .iter()
+        .find(|r| {
+            r.state == HookState::Attached
+                && r.kind != HookKind::Patch
+                && (r.target == address || r.trampoline == address)
+                && (detour == 0 || r.detour == detour)
+        })
+        .cloned()
+}
+
+pub extern "C" fn hook_count() -> u32 {
+    REGISTRY.read().map(|r| r.len() as u32).unwrap_or(0)
+}
+
+/// Fills `info` with the record at `index`, returns false if there is none.
+///
+/// # Safety
+/// `info` must be null or point to writable memory for a `HookInfo`.
+pub unsafe extern "C" fn hook_get(index: u32, info: *mut HookInfo) -> bool {
+    if info.is_null() {
+        return false;
+    }
+
+    let Ok(registry) = REGISTRY.read() else { return false };
+    let Some(record) = registry.get(index as usize) else { return false };
+
+    *info = HookInfo {
+        id: record.id,
+        state: record.state,
+        kind: record.kind,
+        target: record.target as *mut c_void,
+        detour: record.detour as *mut c_void,
+        trampoline: record.trampoline as *mut c_void,
+        attached_at: record.attached_at.timestamp_millis(),
+        name: record.c_name.as_ptr(),
+        owner: record.c_owner.as_ptr(),
+    };
+
+    true
+}
+
+/// Hooks `module`'s imports of `symbol`, storing the original import in `original`. Returns 0 on failure.
+///
+/// # Safety
+/// `module` and `symbol` must be valid C strings, `original` must be null or writable.
+pub unsafe extern "C" fn hook_attach_plt(
+    module: *const c_char,
+    symbol: *const c_char,
+    detour: *mut c_void,
+    original: *mut *mut c_void,
+) -> HookId {
+    if module.is_null() || symbol.is_null() {
+        return 0;
+    }
+
+    let module = CStr::from_ptr(module).to_string_lossy();
+    let symbol = CStr::from_ptr(symbol).to_string_lossy();
+    let name = format!("{module}!{symbol}");
+
+    match attach_plt(&name, OWNER_MANAGED, &module, &symbol, detour as usize) {
+        Ok((id, trampoline)) => {
+            if !original.is_null() {
+                *original = trampoline as *mut c_void;
+            }
+            id
+        }
+        Err(e) => {
+            let _ = crate::error!("Failed to hook {}: {}", name, e.to_string());
+            0
+        }
+    }
+}
+
+pub extern "C" fn hook_detach_id(id: HookId) -> bool {
+    detach(id).is_ok()
+}
+
+pub extern "C" fn hook_detach_all() {
+    detach_all();
+}
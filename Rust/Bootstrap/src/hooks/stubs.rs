@@ -0,0 +1,166 @@
+//! Tiny jump stubs that branch through a writable pointer slot.
+//!
+//! Each arena is a code page followed by a data page. The code page is filled with stubs once and
+//! made executable, every stub jumps to whatever address sits in its slot on the data page, so
+//! retargeting a stub is a single aligned store that is safe while other threads run through it.
+
+use std::{
+    ptr::null_mut,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    },
+};
+
+use lazy_static::lazy_static;
+
+use crate::{
+    errors::{hookerr::HookError, DynErr},
+    hooks::patch::flush_icache,
+};
+
+#[cfg(any(target_arch = "x86_64", target_arch = "x86", target_arch = "aarch64"))]
+const STUB_SIZE: usize = 8;
+#[cfg(target_arch = "arm")]
+const STUB_SIZE: usize = 4;
+
+const SLOT_SIZE: usize = std::mem::size_of::<usize>();
+
+#[derive(Debug)]
+pub struct Stub {
+    /// the address callers jump to
+    pub code: usize,
+    slot: usize,
+}
+
+impl Stub {
+    pub fn target(&self) -> usize {
+        self.slot_ref().load(Ordering::SeqCst)
+    }
+
+    pub fn set_target(&self, target: usize) {
+        self.slot_ref().store(target, Ordering::SeqCst);
+    }
+
+    fn slot_ref(&self) -> &AtomicUsize {
+        unsafe { &*(self.slot as *const AtomicUsize) }
+    }
+}
+
+#[derive(Debug)]
+struct Arena {
+    code: usize,
+    data: usize,
+    used: usize,
+    capacity: usize,
+}
+
+lazy_static! {
+    static ref ARENAS: Mutex<Vec<Arena>> = Mutex::new(Vec::new());
+}
+
+/// Hands out a fresh stub jumping to `target`. Stubs are never freed, a thread might still be running through one.
+pub fn allocate(target: usize) -> Result<Stub, DynErr> {
+    let mut arenas = ARENAS.lock().map_err(|_| HookError::Failed("stub arena".to_string()))?;
+
+    if arenas.last().is_none_or(|a| a.used == a.capacity) {
+        arenas.push(Arena::new()?);
+    }
+
+    let arena = arenas.last_mut().ok_or(HookError::Null)?;
+    let index = arena.used;
+    arena.used += 1;
+
+    let stub = Stub {
+        code: arena.code + index * STUB_SIZE,
+        slot: arena.data + index * SLOT_SIZE,
+    };
+    stub.set_target(target);
+
+    Ok(stub)
+}
+
+impl Arena {
+    fn new() -> Result<Self, DynErr> {
+        let page_size = unsafe { libc::sysconf(libc::_SC_PAGESIZE) } as usize;
+
+        let base = unsafe {
+            libc::mmap(
+                null_mut(),
+                page_size * 2,
+                libc::PROT_READ | libc::PROT_WRITE,
+                libc::MAP_PRIVATE | libc::MAP_ANONYMOUS,
+                -1,
+                0,
+            )
+        };
+
+        if base == libc::MAP_FAILED {
+            return Err(HookError::Failed("stub arena allocation".to_string()).into());
+        }
+
+        let code = base as usize;
+        let data = code + page_size;
+        let capacity = (page_size / STUB_SIZE).min(page_size / SLOT_SIZE);
+
+        for index in 0..capacity {
+            let stub = code + index * STUB_SIZE;
+            let slot = data + index * SLOT_SIZE;
+
+            unsafe { write_stub(stub, slot) };
+        }
+
+        flush_icache(code, page_size);
+
+        unsafe {
+            if libc::mprotect(base, page_size, libc::PROT_READ | libc::PROT_EXEC) != 0 {
+                libc::munmap(base, page_size * 2);
+                return Err(HookError::Failed("stub arena protection".to_string()).into());
+            }
+        }
+
+        Ok(Self { code, data, used: 0, capacity })
+    }
+}
+
+/// `jmp [rip + disp32]`
+#[cfg(target_arch = "x86_64")]
+unsafe fn write_stub(stub: usize, slot: usize) {
+    let disp = (slot as isize - (stub as isize + 6)) as i32;
+
+    let code = stub as *mut u8;
+    code.write(0xFF);
+    code.add(1).write(0x25);
+    (code.add(2) as *mut i32).write_unaligned(disp);
+    code.add(6).write(0xCC);
+    code.add(7).write(0xCC);
+}
+
+/// `jmp [abs32]`
+#[cfg(target_arch = "x86")]
+unsafe fn write_stub(stub: usize, slot: usize) {
+    let code = stub as *mut u8;
+    code.write(0xFF);
+    code.add(1).write(0x25);
+    (code.add(2) as *mut u32).write_unaligned(slot as u32);
+    code.add(6).write(0xCC);
+    code.add(7).write(0xCC);
+}
+
+/// `ldr x17, slot` + `br x17`
+#[cfg(target_arch = "aarch64")]
+unsafe fn write_stub(stub: usize, slot: usize) {
+    let imm19 = (((slot - stub) / 4) as u32) & 0x7FFFF;
+
+    let code = stub as *mut u32;
+    code.write(0x5800_0011 | (imm19 << 5));
+    code.add(1).write(0xD61F_0220);
+}
+
+/// `ldr pc, [pc, #offset]`, pc reads 8 bytes ahead in ARM state
+#[cfg(target_arch = "arm")]
+unsafe fn write_stub(stub: usize, slot: usize) {
+    let offset = (slot - (stub + 8)) as u32;
+
+    (stub as *mut u32).write(0xE59F_F000 | (offset & 0xFFF));
+}
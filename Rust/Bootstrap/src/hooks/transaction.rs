@@ -0,0 +1,296 @@
+//! Groups hook attaches and detaches so they either all apply or none do.
+//!
+//! Operations are only queued until `commit`, which applies them in order. If one fails, the ones
+//! already applied are undone in reverse order, and every operation reports what happened to it.
+//! Attaches go through `hooks::chain`, same as `NativeHookAttach`, so they coexist with other hooks.
+
+use std::{
+    collections::HashMap,
+    ffi::c_void,
+    sync::{
+        atomic::{AtomicU32, Ordering},
+        Mutex,
+    },
+};
+
+use lazy_static::lazy_static;
+
+use crate::{
+    debug, error,
+    errors::{hookerr::HookError, DynErr},
+    hooks::{
+        chain,
+        registry::{self, HookId, HookKind},
+    },
+};
+
+pub type TransactionId = u32;
+
+#[derive(Debug, Clone, Copy)]
+enum Operation {
+    /// `slot` holds the target, and receives the trampoline once applied
+    Attach { slot: *mut *mut c_void, detour: usize },
+    /// `slot` holds the trampoline, and gets the original target back once applied
+    Detach { slot: *mut *mut c_void, detour: usize },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum OperationStatus {
+    Applied,
+    Failed,
+    RolledBack,
+    /// never attempted, an earlier operation failed
+    Skipped,
+}
+
+#[derive(Debug, Clone)]
+pub struct OperationResult {
+    pub target: usize,
+    pub status: OperationStatus,
+    pub hook_id: HookId,
+    pub error: Option<String>,
+}
+
+/// The C view of an `OperationResult`, failures are logged instead of carrying a message.
+#[repr(C)]
+#[derive(Debug)]
+pub struct TransactionResult {
+    pub target: *mut c_void,
+    pub status: OperationStatus,
+    pub hook_id: HookId,
+}
+
+#[derive(Debug, Default)]
+struct Transaction {
+    owner: String,
+    operations: Vec<Operation>,
+}
+
+unsafe impl Send for Transaction {}
+
+lazy_static! {
+    static ref TRANSACTIONS: Mutex<HashMap<TransactionId, Transaction>> = Mutex::new(HashMap::new());
+}
+
+static NEXT_ID: AtomicU32 = AtomicU32::new(1);
+
+pub fn begin(owner: &str) -> TransactionId {
+    let id = NEXT_ID.fetch_add(1, Ordering::SeqCst);
+
+    if let Ok(mut transactions) = TRANSACTIONS.lock() {
+        transactions.insert(id, Transaction { owner: owner.to_string(), operations: Vec::new() });
+    }
+
+    id
+}
+
+/// Queues hooking `*slot` with `detour`. `slot` has to stay valid until the transaction is committed.
+pub fn queue_attach(id: TransactionId, slot: *mut *mut c_void, detour: usize) -> Result<(), DynErr> {
+    queue(id, Operation::Attach { slot, detour })
+}
+
+/// Queues detaching the hook whose trampoline is in `*slot`. `slot` has to stay valid until the transaction is committed.
+pub fn queue_detach(id: TransactionId, slot: *mut *mut c_void, detour: usize) -> Result<(), DynErr> {
+    queue(id, Operation::Detach { slot, detour })
+}
+
+fn queue(id: TransactionId, operation: Operation) -> Result<(), DynErr> {
+    let (Operation::Attach { slot, .. } | Operation::Detach { slot, .. }) = operation;
+    if slot.is_null() {
+        return Err(HookError::Nullpointer("target".to_string()).into());
+    }
+
+    let mut transactions = TRANSACTIONS.lock().map_err(|_| format!("Transaction {id} is unavailable"))?;
+    transactions
+        .get_mut(&id)
+        .ok_or_else(|| format!("No open transaction with id {id}"))?
+        .operations
+        .push(operation);
+
+    Ok(())
+}
+
+/// Throws away a transaction that hasn't been committed, nothing was applied yet.
+pub fn rollback(id: TransactionId) -> Result<(), DynErr> {
+    let mut transactions = TRANSACTIONS.lock().map_err(|_| format!("Transaction {id} is unavailable"))?;
+    transactions.remove(&id).ok_or_else(|| format!("No open transaction with id {id}"))?;
+
+    Ok(())
+}
+
+/// Applies every queued operation, or none of them. Returns whether everything applied, and what happened to each operation.
+pub fn commit(id: TransactionId) -> Result<(bool, Vec<OperationResult>), DynErr> {
+    let transaction = TRANSACTIONS
+        .lock()
+        .map_err(|_| format!("Transaction {id} is unavailable"))?
+        .remove(&id)
+        .ok_or_else(|| format!("No open transaction with id {id}"))?;
+
+    let mut results: Vec<OperationResult> = transaction
+        .operations
+        .iter()
+        .map(|op| {
+            let (Operation::Attach { slot, .. } | Operation::Detach { slot, .. }) = *op;
+            OperationResult {
+                target: unsafe { *slot } as usize,
+                status: OperationStatus::Skipped,
+                hook_id: 0,
+                error: None,
+            }
+        })
+        .collect();
+
+    // where each detached hook sat in its chain, so a rollback can put it back in the same spot
+    let mut positions: Vec<Option<usize>> = vec![None; transaction.operations.len()];
+
+    let mut failed = false;
+    for (index, operation) in transaction.operations.iter().enumerate() {
+        match unsafe { apply(&transaction.owner, *operation) } {
+            Ok((hook_id, position)) => {
+                results[index].status = OperationStatus::Applied;
+                results[index].hook_id = hook_id;
+                positions[index] = position;
+            }
+            Err(e) => {
+                results[index].status = OperationStatus::Failed;
+                results[index].error = Some(e.to_string());
+                failed = true;
+                break;
+            }
+        }
+    }
+
+    if failed {
+        for (index, operation) in transaction.operations.iter().enumerate().rev() {
+            if results[index].status != OperationStatus::Applied {
+                continue;
+            }
+
+            match unsafe { undo(&transaction.owner, *operation, results[index].hook_id, positions[index]) } {
+                Ok(hook_id) => {
+                    results[index].status = OperationStatus::RolledBack;
+                    results[index].hook_id = hook_id;
+                }
+                Err(e) => {
+                    let _ = error!("Failed to roll back hook on {:#x}: {}", results[index].target, e.to_string());
+                }
+            }
+        }
+    }
+
+    debug!(
+        "Transaction {} ({}) {} {} operation(s)",
+        id,
+        transaction.owner,
+        if failed { "rolled back" } else { "applied" },
+        results.len()
+    )?;
+
+    Ok((!failed, results))
+}
+
+/// Returns the hook id, and for a detached chained hook, where it sat in its chain.
+unsafe fn apply(owner: &str, operation: Operation) -> Result<(HookId, Option<usize>), DynErr> {
+    match operation {
+        Operation::Attach { slot, detour } => {
+            let target = *slot as usize;
+            let (id, trampoline) = registry::attach_chained(&format!("{target:#x}"), owner, target, detour)?;
+            *slot = trampoline as *mut c_void;
+            Ok((id, None))
+        }
+        Operation::Detach { slot, detour } => {
+            let record = registry::find_attached(*slot as usize, detour)
+                .ok_or_else(|| format!("{:#x} is not hooked", *slot as usize))?;
+
+            let position = match record.kind {
+                HookKind::Chained => chain::position(record.target, record.detour),
+                _ => None,
+            };
+
+            registry::detach(record.id)?;
+            *slot = record.target as *mut c_void;
+            Ok((record.id, position))
+        }
+    }
+}
+
+/// Reverses an applied operation. Re-attaching a detached hook creates a new trampoline, which is written back into the slot.
+/// A hook that was detached from the middle of a chain goes back to `position`, so the chain keeps its order.
+unsafe fn undo(owner: &str, operation: Operation, hook_id: HookId, position: Option<usize>) -> Result<HookId, DynErr> {
+    let record = registry::get(hook_id).ok_or_else(|| format!("No hook with id {hook_id}"))?;
+
+    match operation {
+        Operation::Attach { slot, .. } => {
+            registry::detach(hook_id)?;
+            *slot = record.target as *mut c_void;
+            Ok(hook_id)
+        }
+        Operation::Detach { slot, .. } => {
+            let (id, trampoline) = match position {
+                Some(index) => registry::attach_chained_at(&record.name, owner, record.target, record.detour, index)?,
+                None => registry::attach_chained(&record.name, owner, record.target, record.detour)?,
+            };
+            *slot = trampoline as *mut c_void;
+            Ok(id)
+        }
+    }
+}
+
+pub extern "C" fn transaction_begin() -> TransactionId {
+    begin(registry::OWNER_MANAGED)
+}
+
+pub extern "C" fn transaction_attach(id: TransactionId, target: *mut *mut c_void, detour: *mut c_void) -> bool {
+    report(queue_attach(id, target, detour as usize))
+}
+
+pub extern "C" fn transaction_detach(id: TransactionId, target: *mut *mut c_void, detour: *mut c_void) -> bool {
+    report(queue_detach(id, target, detour as usize))
+}
+
+pub extern "C" fn transaction_rollback(id: TransactionId) -> bool {
+    report(rollback(id))
+}
+
+/// Commits the transaction, writing up to `max` per-operation results into `results`. Returns true if everything applied.
+///
+/// # Safety
+/// `results` must be null or have room for `max` entries.
+pub unsafe extern "C" fn transaction_commit(id: TransactionId, results: *mut TransactionResult, max: u32) -> bool {
+    let (applied, operations) = match commit(id) {
+        Ok(result) => result,
+        Err(e) => {
+            let _ = error!("Failed to commit hook transaction: {}", e.to_string());
+            return false;
+        }
+    };
+
+    for operation in operations.iter().filter(|o| o.status == OperationStatus::Failed) {
+        let _ = error!(
+            "Hook on {:#x} failed: {}",
+            operation.target,
+            operation.error.as_deref().unwrap_or_default()
+        );
+    }
+
+    if !results.is_null() {
+        for (i, operation) in operations.iter().take(max as usize).enumerate() {
+            *results.add(i) = TransactionResult {
+                target: operation.target as *mut c_void,
+                status: operation.status,
+                hook_id: operation.hook_id,
+            };
+        }
+    }
+
+    applied
+}
+
+fn report(result: Result<(), DynErr>) -> bool {
+    result
+        .map_err(|e| {
+            let _ = error!("Hook transaction failed: {}", e.to_string());
+        })
+        .is_ok()
+}
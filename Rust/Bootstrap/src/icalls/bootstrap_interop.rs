@@ -1,26 +1,122 @@
-use std::ffi::c_void;
+use std::{
+    cell::RefCell,
+    ffi::{c_char, c_void, CString},
+};
 
-use crate::{error, hooks::{self, NativeHook}};
+use crate::{
+    error,
+    errors::{hookerr::HookError, DynErr},
+    hooks::registry,
+};
 
-pub unsafe fn attach(target: *mut *mut c_void, detour: *mut c_void) {
-    // match NativeHook::<fn()>::new(*target as usize, detour as usize).hook() {
-    //     Ok(res) => *target = res.trampoline as *mut c_void,
-    //     Err(e) => {
-    //         let _ = error!("Failed to hook function: {}", e.to_string());
-    //     }
-    // };
-
-    let mut hook = NativeHook::<fn()>::new(*target, detour);
-    match hook.hook() {
-        Ok(_) => *target = hook.trampoline as *mut c_void,
-        Err(e) => {
-            let _ = error!("Failed to hook function: {}", e.to_string());
+/// What `NativeHookAttachEx` and `NativeHookDetachEx` hand back to managed code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(i32)]
+pub enum HookStatus {
+    Ok = 0,
+    /// the hook produced a null trampoline
+    Null = 1,
+    /// the target or detour was null
+    Nullpointer = 2,
+    Failed = 3,
+    /// Dobby itself refused the hook
+    Dobby = 4,
+    NotHooked = 5,
+    /// the target or detour isn't mapped executable
+    NotExecutable = 6,
+}
+
+impl From<&DynErr> for HookStatus {
+    fn from(e: &DynErr) -> Self {
+        match e.downcast_ref::<HookError>() {
+            Some(HookError::Null) => HookStatus::Null,
+            Some(HookError::Nullpointer(_)) => HookStatus::Nullpointer,
+            Some(HookError::Dobby(_)) => HookStatus::Dobby,
+            Some(HookError::NotHooked(_)) => HookStatus::NotHooked,
+            Some(HookError::NotExecutable(_)) => HookStatus::NotExecutable,
+            _ => HookStatus::Failed,
         }
-    };
+    }
 }
 
-pub unsafe fn detach(target: *mut *mut c_void, _detour: *mut c_void) {
-    hooks::functions::unhook(*target as usize).unwrap_or_else(|e| {
+thread_local! {
+    /// kept as a C string so `get_last_error` can hand out a pointer to it
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+pub unsafe fn attach(target: *mut *mut c_void, detour: *mut c_void) {
+    if let Err(e) = try_attach(target, detour) {
+        let _ = error!("Failed to hook function: {}", e.to_string());
+    }
+}
+
+pub unsafe fn detach(target: *mut *mut c_void, detour: *mut c_void) {
+    if let Err(e) = try_detach(target, detour) {
         let _ = error!("Failed to unhook function: {}", e.to_string());
+    }
+}
+
+/// Like `attach`, but reports the outcome instead of only logging it.
+///
+/// # Safety
+/// `target` must be null or point to a writable function pointer.
+pub unsafe extern "C" fn attach_ex(target: *mut *mut c_void, detour: *mut c_void) -> HookStatus {
+    report(try_attach(target, detour))
+}
+
+/// Like `detach`, but reports the outcome instead of only logging it.
+///
+/// # Safety
+/// `target` must be null or point to a writable function pointer.
+pub unsafe extern "C" fn detach_ex(target: *mut *mut c_void, detour: *mut c_void) -> HookStatus {
+    report(try_detach(target, detour))
+}
+
+/// The message of the last failed `attach_ex` or `detach_ex` on this thread, or null if it succeeded.
+/// The string is owned by the Bootstrap and stays valid until the next `attach_ex` or `detach_ex` on this thread.
+pub extern "C" fn get_last_error() -> *const c_char {
+    LAST_ERROR.with_borrow(|last| match last {
+        Some(message) => message.as_ptr(),
+        None => std::ptr::null(),
+    })
+}
+
+unsafe fn try_attach(target: *mut *mut c_void, detour: *mut c_void) -> Result<(), DynErr> {
+    if target.is_null() {
+        return Err(HookError::Nullpointer("target".to_string()).into());
+    }
+
+    let name = format!("{:#x}", *target as usize);
+    let (_, trampoline) = registry::attach_chained(&name, registry::OWNER_MANAGED, *target as usize, detour as usize)?;
+    *target = trampoline as *mut c_void;
+
+    Ok(())
+}
+
+unsafe fn try_detach(target: *mut *mut c_void, detour: *mut c_void) -> Result<(), DynErr> {
+    if target.is_null() {
+        return Err(HookError::Nullpointer("target".to_string()).into());
+    }
+
+    // after attaching, managed code only has the trampoline left in `target`
+    let record = registry::find_attached(*target as usize, detour as usize)
+        .ok_or_else(|| HookError::NotHooked(format!("{:#x}", *target as usize)))?;
+
+    registry::detach(record.id)?;
+    *target = record.target as *mut c_void;
+
+    Ok(())
+}
+
+fn report(result: Result<(), DynErr>) -> HookStatus {
+    let status = match &result {
+        Ok(_) => HookStatus::Ok,
+        Err(e) => HookStatus::from(e),
+    };
+
+    LAST_ERROR.with_borrow_mut(|last| {
+        *last = result.err().map(|e| CString::new(e.to_string().replace('\0', "")).unwrap_or_default())
     });
+
+    status
 }
@@ -1,6 +1,9 @@
 use unity_rs::{common::method::MethodPointer, runtime::FerrexRuntime};
 
-use crate::{core_android, debug, errors::DynErr, logging::logger, melonenv::paths};
+use crate::{
+    debug, errors::DynErr, hooks::{patch, registry, transaction}, logging::logger, melonenv::paths, phase, platform,
+    utils::{exports, modules, sigscan, symbols},
+};
 
 mod melon_utils;
 pub mod bootstrap_interop;
@@ -11,17 +14,40 @@ mod preload;
 pub fn init(runtime: &FerrexRuntime) -> Result<(), DynErr> {
     debug!("Initializing internal calls")?;
 
-    runtime.add_internal_call("MelonLoader.MelonUtils::IsGame32Bit", melon_utils::is_32_bit as MethodPointer)?;
-    runtime.add_internal_call("MelonLoader.BootstrapInterop::NativeHookAttach", bootstrap_interop::attach as MethodPointer)?;
-    runtime.add_internal_call("MelonLoader.BootstrapInterop::NativeHookDetach", bootstrap_interop::detach as MethodPointer)?;
-    runtime.add_internal_call("MelonLoader.BootstrapInterop::NativeLogConsole", logger::log_console_interop as MethodPointer)?;
-    runtime.add_internal_call("MelonLoader.BootstrapInterop::NativeGetJavaVM", core_android::get_raw_java_vm as MethodPointer)?;
-    runtime.add_internal_call("MelonLoader.BootstrapInterop::NativeGetPackageName", paths::get_package_name_raw as MethodPointer)?;
-    runtime.add_internal_call("MelonLoader.Utils.MonoLibrary::GetLibPtr", mono_library::get_lib_ptr as MethodPointer)?;
-    runtime.add_internal_call("MelonLoader.Utils.MonoLibrary::CastManagedAssemblyPtr", mono_library::cast_assembly_ptr as MethodPointer)?;
-    runtime.add_internal_call("MelonLoader.Utils.MonoLibrary::GetRootDomainPtr", mono_library::get_domain_ptr as MethodPointer)?;
-    runtime.add_internal_call("MelonLoader.Resolver.AssemblyManager::InstallHooks", resolve_internals::install_hooks as MethodPointer)?;
-    runtime.add_internal_call("MelonLoader.Support.Preload::GetManagedDirectory", preload::get_managed_dir as MethodPointer)?;
+    exports::add_internal_call(runtime, "MelonLoader.MelonUtils::IsGame32Bit", melon_utils::is_32_bit as MethodPointer)?;
+    exports::add_internal_call(runtime, "MelonLoader.BootstrapInterop::NativeHookAttach", bootstrap_interop::attach as MethodPointer)?;
+    exports::add_internal_call(runtime, "MelonLoader.BootstrapInterop::NativeHookDetach", bootstrap_interop::detach as MethodPointer)?;
+    exports::add_internal_call(runtime, "MelonLoader.BootstrapInterop::NativeHookAttachEx", bootstrap_interop::attach_ex as MethodPointer)?;
+    exports::add_internal_call(runtime, "MelonLoader.BootstrapInterop::NativeHookDetachEx", bootstrap_interop::detach_ex as MethodPointer)?;
+    exports::add_internal_call(runtime, "MelonLoader.BootstrapInterop::NativeHookGetLastErrorPtr", bootstrap_interop::get_last_error as MethodPointer)?;
+    exports::add_internal_call(runtime, "MelonLoader.BootstrapInterop::NativeHookCount", registry::hook_count as MethodPointer)?;
+    exports::add_internal_call(runtime, "MelonLoader.BootstrapInterop::NativeHookGet", registry::hook_get as MethodPointer)?;
+    exports::add_internal_call(runtime, "MelonLoader.BootstrapInterop::NativeHookDetachId", registry::hook_detach_id as MethodPointer)?;
+    exports::add_internal_call(runtime, "MelonLoader.BootstrapInterop::NativeHookDetachAll", registry::hook_detach_all as MethodPointer)?;
+    exports::add_internal_call(runtime, "MelonLoader.BootstrapInterop::NativeHookAttachPlt", registry::hook_attach_plt as MethodPointer)?;
+    exports::add_internal_call(runtime, "MelonLoader.BootstrapInterop::NativeTransactionBegin", transaction::transaction_begin as MethodPointer)?;
+    exports::add_internal_call(runtime, "MelonLoader.BootstrapInterop::NativeTransactionAttach", transaction::transaction_attach as MethodPointer)?;
+    exports::add_internal_call(runtime, "MelonLoader.BootstrapInterop::NativeTransactionDetach", transaction::transaction_detach as MethodPointer)?;
+    exports::add_internal_call(runtime, "MelonLoader.BootstrapInterop::NativeTransactionCommit", transaction::transaction_commit as MethodPointer)?;
+    exports::add_internal_call(runtime, "MelonLoader.BootstrapInterop::NativeTransactionRollback", transaction::transaction_rollback as MethodPointer)?;
+    exports::add_internal_call(runtime, "MelonLoader.BootstrapInterop::NativeSigScan", sigscan::sig_scan as MethodPointer)?;
+    exports::add_internal_call(runtime, "MelonLoader.BootstrapInterop::NativeModuleCount", modules::module_count as MethodPointer)?;
+    exports::add_internal_call(runtime, "MelonLoader.BootstrapInterop::NativeModuleGet", modules::module_get as MethodPointer)?;
+    exports::add_internal_call(runtime, "MelonLoader.BootstrapInterop::NativeModuleResolve", modules::module_resolve as MethodPointer)?;
+    exports::add_internal_call(runtime, "MelonLoader.BootstrapInterop::NativeSymbolResolve", symbols::symbol_resolve as MethodPointer)?;
+    exports::add_internal_call(runtime, "MelonLoader.BootstrapInterop::NativePatchWrite", patch::patch_write as MethodPointer)?;
+    exports::add_internal_call(runtime, "MelonLoader.BootstrapInterop::NativePatchNop", patch::patch_nop as MethodPointer)?;
+    exports::add_internal_call(runtime, "MelonLoader.BootstrapInterop::NativePatchWritePointer", patch::patch_write_pointer as MethodPointer)?;
+    exports::add_internal_call(runtime, "MelonLoader.BootstrapInterop::NativeLogConsole", logger::log_console_interop as MethodPointer)?;
+    exports::add_internal_call(runtime, "MelonLoader.BootstrapInterop::NativeGetJavaVM", platform::get_raw_java_vm as MethodPointer)?;
+    exports::add_internal_call(runtime, "MelonLoader.BootstrapInterop::NativeGetPackageName", paths::get_package_name_raw as MethodPointer)?;
+    exports::add_internal_call(runtime, "MelonLoader.BootstrapInterop::NativeGetLoaderPhase", phase::melonloader_get_loader_phase as MethodPointer)?;
+    exports::add_internal_call(runtime, "MelonLoader.BootstrapInterop::NativeGetFailedPhase", phase::melonloader_get_failed_phase as MethodPointer)?;
+    exports::add_internal_call(runtime, "MelonLoader.Utils.MonoLibrary::GetLibPtr", mono_library::get_lib_ptr as MethodPointer)?;
+    exports::add_internal_call(runtime, "MelonLoader.Utils.MonoLibrary::CastManagedAssemblyPtr", mono_library::cast_assembly_ptr as MethodPointer)?;
+    exports::add_internal_call(runtime, "MelonLoader.Utils.MonoLibrary::GetRootDomainPtr", mono_library::get_domain_ptr as MethodPointer)?;
+    exports::add_internal_call(runtime, "MelonLoader.Resolver.AssemblyManager::InstallHooks", resolve_internals::install_hooks as MethodPointer)?;
+    exports::add_internal_call(runtime, "MelonLoader.Support.Preload::GetManagedDirectory", preload::get_managed_dir as MethodPointer)?;
 
     Ok(())
 }
\ No newline at end of file
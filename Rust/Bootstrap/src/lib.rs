@@ -13,6 +13,8 @@
 // #![allow(clippy::inherent_to_string, clippy::type_complexity, improper_ctypes)]
 // #![cfg_attr(docsrs, feature(doc_cfg))]
 
+#[cfg(unix)]
+pub mod abi;
 pub mod base_assembly;
 pub mod console;
 pub mod constants;
@@ -21,10 +23,23 @@ pub mod hooks;
 pub mod icalls;
 pub mod logging;
 pub mod melonenv;
+#[cfg(unix)]
+pub mod native_mods;
+pub mod phase;
+pub mod safe_mode;
+#[cfg(unix)]
+pub mod signals;
 pub mod utils;
 
 pub mod core;
 #[cfg(target_os = "android")]
 pub mod core_android;
+#[cfg(target_os = "linux")]
+pub mod core_linux;
 #[cfg(target_os = "android")]
-pub mod dotnet_trace;
\ No newline at end of file
+pub mod dotnet_trace;
+
+#[cfg(target_os = "android")]
+pub use core_android as platform;
+#[cfg(target_os = "linux")]
+pub use core_linux as platform;
@@ -1,6 +1,6 @@
 /// Throws an internal failure with the given message
 ///
-/// This logs your message to file, creates a message box, and then panics.
+/// This logs your message to file, marks the current loader phase as failed, and then panics.
 /// It uses the same syntax as _format!_
 ///
 /// # Examples
@@ -16,6 +16,33 @@ macro_rules! internal_failure {
         let mut prefix: String = "INTERNAL FAILURE: ".to_string();
         prefix.push_str(msg);
         let _ = $crate::logging::logger::log_console_file($crate::logging::logger::LogLevel::Error, prefix.as_str());
+        $crate::phase::fail(msg);
         panic!();
     }};
 }
+
+/// Reports a failure the game can survive, like a detour failing after the original was called.
+///
+/// When safe mode is enabled this logs your message, detaches every hook, and lets the game
+/// carry on without MelonLoader. Otherwise it behaves exactly like _internal_failure!_.
+///
+/// # Examples
+///
+/// ```
+/// # use utils::assert;
+/// assert::recoverable_failure!("This is a recoverable failure");
+/// ```
+#[macro_export]
+macro_rules! recoverable_failure {
+    ($($arg:tt)*) => {{
+        if $crate::safe_mode::is_enabled() {
+            let msg = &format_args!($($arg)*).to_string();
+            let mut prefix: String = "FAILURE: ".to_string();
+            prefix.push_str(msg);
+            let _ = $crate::logging::logger::log_console_file($crate::logging::logger::LogLevel::Error, prefix.as_str());
+            $crate::safe_mode::engage(msg);
+        } else {
+            $crate::internal_failure!($($arg)*);
+        }
+    }};
+}
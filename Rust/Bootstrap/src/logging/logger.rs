@@ -3,7 +3,14 @@ use crate::{
     errors::{logerr::LogError, DynErr},
 };
 use colored::Colorize;
-use std::{io::Write, ffi::{CString, c_char}, sync::Arc};
+use std::{
+    ffi::{c_char, CString},
+    io::Write,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
 
 #[cfg(target_os = "android")]
 use android_liblog_sys::{__android_log_write, LogPriority};
@@ -17,6 +24,7 @@ pub enum LogLevel {
     Debug,
 }
 
+#[cfg(target_os = "android")]
 lazy_static::lazy_static! {
     static ref MELON_LOADER_TAG: Arc<CString> = {
         CString::new("MelonLoader").expect("CString conversion failed").into()
@@ -37,6 +45,9 @@ impl std::convert::TryFrom<u8> for LogLevel {
     }
 }
 
+/// Set once the log has been closed during shutdown, nothing gets written to the file after that.
+static CLOSED: AtomicBool = AtomicBool::new(false);
+
 macro_rules! log_path {
     () => {
         $crate::melonenv::paths::BASE_DIR.clone().join("MelonLoader").join("Latest-Bootstrap.log")
@@ -53,7 +64,21 @@ pub fn init() -> Result<(), DynErr> {
     Ok(())
 }
 
+/// Flushes the console and stops writing to the log file.
+pub fn close() {
+    if CLOSED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+
+    let _ = std::io::stdout().flush();
+    let _ = std::io::stderr().flush();
+}
+
 fn write(msg: &str) -> Result<(), DynErr> {
+    if CLOSED.load(Ordering::SeqCst) {
+        return Ok(());
+    }
+
     let log_file = log_path!();
 
     let mut file = std::fs::OpenOptions::new()
@@ -131,6 +156,7 @@ pub unsafe fn log_console_interop(input: *const c_char) {
     crate::log_console!(LogLevel::Info, "{}", input);
 }
 
+#[cfg(target_os = "android")]
 #[macro_export]
 macro_rules! log_console {
     ($level:expr, $($arg:tt)*) => {
@@ -149,6 +175,17 @@ macro_rules! log_console {
     };
 }
 
+#[cfg(not(target_os = "android"))]
+#[macro_export]
+macro_rules! log_console {
+    ($level:expr, $($arg:tt)*) => {
+        match $level {
+            LogLevel::Error | LogLevel::Warning => eprintln!($($arg)*),
+            LogLevel::Info | LogLevel::Debug => println!($($arg)*),
+        }
+    };
+}
+
 /// Logs a message to the console and log file
 ///
 /// # Example
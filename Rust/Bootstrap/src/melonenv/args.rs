@@ -1,9 +1,7 @@
-use clap::Parser;
+use clap::{ArgAction, Parser};
 use lazy_static::lazy_static;
 
-use crate::{internal_failure};
-
-#[derive(Debug, Parser)]
+#[derive(Debug, Default, Parser)]
 #[command(author, version, about, long_about = None)]
 pub struct Cli {
     #[arg(long = "melonloader.debug")]
@@ -20,12 +18,17 @@ pub struct Cli {
 
     #[arg(long = "melonloader.basedir")]
     pub base_dir: Option<String>,
+
+    #[arg(long = "melonloader.dotnetroot")]
+    pub dotnet_root: Option<String>,
+
+    #[arg(long = "melonloader.safemode", action = ArgAction::SetTrue)]
+    pub safe_mode: bool,
 }
 
 lazy_static! {
     pub static ref ARGS: Cli = {
-        Cli::parse_optimistic().unwrap_or_else(|e| {
-            internal_failure!("Failed to parse command line arguments: {}", e.to_string());
-        })
+        // launchers pass flags like a bare --melonloader.debug, which shouldn't take startup down with it
+        Cli::parse_optimistic().unwrap_or_default()
     };
 }
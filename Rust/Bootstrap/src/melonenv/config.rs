@@ -0,0 +1,70 @@
+//! Bootstrap settings, read from `UserData/Bootstrap.json`.
+//!
+//! Every field has a default, so the file only needs to contain what you want to change.
+
+use std::{collections::BTreeMap, path::PathBuf};
+
+use lazy_static::lazy_static;
+use serde::Deserialize;
+
+use crate::{errors::DynErr, melonenv::paths, warn};
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct BootstrapConfig {
+    /// Detach everything and let the game run vanilla when startup fails, instead of crashing it.
+    pub safe_mode: bool,
+
+    /// When the invoke hook should start MelonLoader.
+    pub start_trigger: StartTriggerConfig,
+
+    /// Tweaks applied to the .NET runtime before it starts.
+    pub runtime: RuntimeSettings,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct StartTriggerConfig {
+    /// Method names to start on, like `Internal_ActiveSceneChanged`, or class qualified names like
+    /// `UnityEngine.SceneManagement.SceneManager::Internal_ActiveSceneChanged`.
+    /// Leaving this out uses the defaults for the game's runtime.
+    pub methods: Option<Vec<String>>,
+
+    /// Start on the first invoke this many milliseconds after the invoke hook was attached.
+    pub after_ms: Option<u64>,
+
+    /// Start anyway on the first invoke after this many milliseconds, if nothing else fired by then.
+    pub timeout_ms: Option<u64>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct RuntimeSettings {
+    /// hostfxr runtime properties, like `System.GC.Server` or `System.Globalization.Invariant`.
+    /// Booleans and numbers are passed on as their string form.
+    pub properties: BTreeMap<String, serde_json::Value>,
+
+    /// Environment variables to set before the runtime starts, usually `DOTNET_*` knobs.
+    pub environment: BTreeMap<String, String>,
+}
+
+lazy_static! {
+    pub static ref CONFIG: BootstrapConfig = load().unwrap_or_else(|e| {
+        let _ = warn!("Failed to read {}: {}, using defaults", config_path().display(), e.to_string());
+        BootstrapConfig::default()
+    });
+}
+
+pub fn config_path() -> PathBuf {
+    paths::USER_DATA_FOLDER.join("Bootstrap.json")
+}
+
+fn load() -> Result<BootstrapConfig, DynErr> {
+    let path = config_path();
+    if !path.exists() {
+        return Ok(BootstrapConfig::default());
+    }
+
+    let contents = std::fs::read_to_string(path)?;
+    Ok(serde_json::from_str(&contents)?)
+}
@@ -32,4 +32,13 @@ macro_rules! hide_console {
         let args: Vec<String> = std::env::args().collect();
         args.contains(&"--melonloader.hideconsole".to_string())
     }};
-}
\ No newline at end of file
+}
+
+#[macro_export]
+macro_rules! safe_mode_enabled {
+    () => {{
+        // scanned like the other flags, ARGS parsing must not be able to fail the recovery path
+        let args: Vec<String> = std::env::args().collect();
+        args.contains(&"--melonloader.safemode".to_string()) || $crate::melonenv::config::CONFIG.safe_mode
+    }};
+}
@@ -1,3 +1,4 @@
 pub mod args;
+pub mod config;
 pub mod macros;
 pub mod paths;
@@ -29,23 +29,32 @@ lazy_static! {
         let args: Vec<String> = std::env::args().collect();
         let mut base_dir =
             current_dir().unwrap_or_else(|e| internal_failure!("Failed to get game dir: {e}"));
-        for arg in args.iter() {
-            if arg.starts_with("--melonloader.basedir") {
-                let a: Vec<&str> = arg.split("=").collect();
-                base_dir = PathBuf::from(a[1]);
+
+        // on desktop the game always lives next to the executable, only the base dir can be moved
+        if cfg!(target_os = "android") {
+            for arg in args.iter() {
+                if arg.starts_with("--melonloader.basedir") {
+                    let a: Vec<&str> = arg.split("=").collect();
+                    base_dir = PathBuf::from(a[1]);
+                }
             }
         }
 
         W(base_dir)
     };
     pub static ref MELONLOADER_FOLDER: W<PathBuf> = W(BASE_DIR.join("MelonLoader"));
+    pub static ref USER_DATA_FOLDER: W<PathBuf> = W(BASE_DIR.join("UserData"));
     pub static ref DEPENDENCIES_FOLDER: W<PathBuf> = W(MELONLOADER_FOLDER.join("Dependencies"));
     pub static ref SUPPORT_MODULES_FOLDER: W<PathBuf> =
         W(DEPENDENCIES_FOLDER.join("SupportModules"));
     pub static ref PRELOAD_DLL: W<PathBuf> = W(SUPPORT_MODULES_FOLDER.join("Preload.dll"));
+    pub static ref NATIVE_MODS_FOLDER: W<PathBuf> = W(MELONLOADER_FOLDER.join("NativeMods"));
+    pub static ref SYMBOLS_FOLDER: W<PathBuf> = W(DEPENDENCIES_FOLDER.join("Symbols"));
 }
 
+#[cfg(target_os = "android")]
 static mut DATA_DIR: Option<String> = None;
+#[cfg(target_os = "android")]
 static mut PACKAGE_NAME: Option<String> = None;
 
 pub fn runtime_dir() -> Result<PathBuf, DynErr> {
@@ -89,16 +98,33 @@ pub fn get_managed_dir() -> Result<PathBuf, DynErr> {
     }
 }
 
+#[cfg(target_os = "android")]
 pub fn get_internal_data_path() -> Result<PathBuf, DynErr> {
     Ok(PathBuf::from("/data/data/").join(get_package_name()?))
 }
 
+#[cfg(not(target_os = "android"))]
+pub fn get_internal_data_path() -> Result<PathBuf, DynErr> {
+    Ok(BASE_DIR.to_path_buf())
+}
+
+#[cfg(target_os = "android")]
 pub fn get_dotnet_path() -> Result<PathBuf, DynErr> {
     Ok(PathBuf::from("/data/data/")
         .join(get_package_name()?)
         .join("dotnet"))
 }
 
+/// On desktop the runtime is shipped next to the game, unless `--melonloader.dotnetroot` says otherwise.
+#[cfg(not(target_os = "android"))]
+pub fn get_dotnet_path() -> Result<PathBuf, DynErr> {
+    match &crate::melonenv::args::ARGS.dotnet_root {
+        Some(dotnet_root) => Ok(PathBuf::from(dotnet_root)),
+        None => Ok(get_internal_data_path()?.join("dotnet")),
+    }
+}
+
+#[cfg(target_os = "android")]
 pub fn get_package_name() -> Result<String, DynErr> {
     unsafe {
         match &PACKAGE_NAME.clone() {
@@ -108,6 +134,20 @@ pub fn get_package_name() -> Result<String, DynErr> {
     }
 }
 
+/// There are no packages on desktop, so the executable name is the closest thing we have.
+#[cfg(not(target_os = "android"))]
+pub fn get_package_name() -> Result<String, DynErr> {
+    let file_path = std::env::current_exe()?;
+
+    let file_name = file_path
+        .file_stem()
+        .ok_or_else(|| "Failed to get File Stem!")?
+        .to_str()
+        .ok_or_else(|| "Failed to get File Stem!")?;
+
+    Ok(file_name.to_string())
+}
+
 pub unsafe fn get_package_name_raw() -> *const libc::c_char {
     use std::ffi::CString;
     let package_name = get_package_name().unwrap_or_else(|e| {
@@ -118,6 +158,7 @@ pub unsafe fn get_package_name_raw() -> *const libc::c_char {
     raw_string.into_raw()
 }
 
+#[cfg(target_os = "android")]
 pub fn current_dir() -> Result<PathBuf, DynErr> {
     unsafe {
         match DATA_DIR.clone() {
@@ -127,6 +168,14 @@ pub fn current_dir() -> Result<PathBuf, DynErr> {
     }
 }
 
+#[cfg(not(target_os = "android"))]
+pub fn current_dir() -> Result<PathBuf, DynErr> {
+    let file_path = std::env::current_exe()?;
+    let base_folder = file_path.parent().ok_or_else(|| "Failed to get executable directory!")?;
+
+    Ok(base_folder.to_path_buf())
+}
+
 #[cfg(target_os = "android")]
 pub fn cache_data_dir(env: &mut JNIEnv) {
     let unity_class_name = "com/unity3d/player/UnityPlayer";
@@ -207,6 +256,7 @@ pub fn cache_data_dir(env: &mut JNIEnv) {
     }
 }
 
+#[cfg(target_os = "android")]
 const BAD_HASHES: [&str; 18] = [
     "95fb4cd16729627d013dc620a807c23c",
     "ffaf599e1b7e1175cd344b367e4a7ec4",
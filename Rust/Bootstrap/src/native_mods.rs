@@ -0,0 +1,155 @@
+//! Loads native plugins, shared libraries dropped into `MelonLoader/NativeMods`.
+//!
+//! Each plugin can export any of these, all `extern "C" fn() -> i32`, returning 0 on success:
+//!
+//! - `melon_native_load`, right after the plugin is loaded, before the runtime is up
+//! - `melon_native_init`, once the runtime is initialized
+//! - `melon_native_start`, once MelonLoader has started
+//!
+//! A plugin that fails an entrypoint doesn't get called again. Plugins can use the Bootstrap's own services
+//! through the C ABI in `include/melonloader.h`. If startup fails, every plugin's status is logged.
+
+use std::{ffi::c_int, path::PathBuf, sync::Mutex};
+
+use lazy_static::lazy_static;
+
+use crate::{
+    debug, error,
+    errors::DynErr,
+    log,
+    melonenv::paths,
+    phase::{self, LoaderPhase},
+};
+use shared::libloading::{load_lib, NativeLibrary, NativeMethod};
+
+type EntryPoint = extern "C" fn() -> c_int;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NativeModStatus {
+    Loaded,
+    Failed(String),
+}
+
+#[derive(Debug)]
+pub struct NativeMod {
+    pub library: NativeLibrary,
+    pub status: NativeModStatus,
+}
+
+lazy_static! {
+    static ref NATIVE_MODS: Mutex<Vec<NativeMod>> = Mutex::new(Vec::new());
+}
+
+/// Loads every plugin in the NativeMods folder, calls their load entrypoints, and has the rest called as phases are reached.
+pub fn init() -> Result<(), DynErr> {
+    let plugins = discover()?;
+    if plugins.is_empty() {
+        return Ok(());
+    }
+
+    let mut loaded = 0;
+    for path in plugins {
+        match load_lib(&path, libc::RTLD_NOW | libc::RTLD_LOCAL) {
+            Ok(library) => {
+                // not holding the lock, the plugin may call back into us
+                let status = call(&library.name, "melon_native_load", library.sym("melon_native_load").ok());
+                if status == NativeModStatus::Loaded {
+                    loaded += 1;
+                }
+
+                NATIVE_MODS.lock()?.push(NativeMod { library, status });
+            }
+            Err(e) => error!("Failed to load native mod {}: {}", path.display(), e.to_string())?,
+        }
+    }
+
+    log!("Loaded {} native mod(s)", loaded);
+
+    phase::subscribe(on_phase);
+
+    Ok(())
+}
+
+/// Shared libraries in the NativeMods folder, sorted by name so the load order is predictable.
+fn discover() -> Result<Vec<PathBuf>, DynErr> {
+    let dir = &paths::NATIVE_MODS_FOLDER;
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut plugins: Vec<PathBuf> = std::fs::read_dir(&**dir)?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.is_file() && path.extension().is_some_and(|ext| ext == "so"))
+        .collect();
+
+    plugins.sort();
+
+    Ok(plugins)
+}
+
+fn on_phase(phase: LoaderPhase) {
+    let entrypoint = match phase {
+        LoaderPhase::RuntimeInitialized => "melon_native_init",
+        LoaderPhase::Started => "melon_native_start",
+        LoaderPhase::Failed => return report(),
+        _ => return,
+    };
+
+    // copied out so a plugin can call back into us without deadlocking
+    let functions: Vec<(usize, String, Option<NativeMethod<EntryPoint>>)> = match NATIVE_MODS.lock() {
+        Ok(mods) => mods
+            .iter()
+            .enumerate()
+            .filter(|(_, m)| m.status == NativeModStatus::Loaded)
+            .map(|(index, m)| (index, m.library.name.clone(), m.library.sym(entrypoint).ok()))
+            .collect(),
+        Err(_) => return,
+    };
+
+    for (index, name, function) in functions {
+        let status = call(&name, entrypoint, function);
+        if status == NativeModStatus::Loaded {
+            continue;
+        }
+
+        if let Ok(mut mods) = NATIVE_MODS.lock() {
+            mods[index].status = status;
+        }
+    }
+}
+
+/// Calls `entrypoint` if the plugin exports it, returning the plugin's new status.
+fn call(name: &str, entrypoint: &str, function: Option<NativeMethod<EntryPoint>>) -> NativeModStatus {
+    let Some(function) = function else {
+        let _ = debug!("Native mod {} has no {}", name, entrypoint);
+        return NativeModStatus::Loaded;
+    };
+
+    let result = function();
+    if result == 0 {
+        let _ = debug!("Native mod {}: {} succeeded", name, entrypoint);
+        return NativeModStatus::Loaded;
+    }
+
+    let reason = format!("{entrypoint} returned {result}");
+    let _ = error!("Native mod {} failed, {}. It won't be called again", name, reason);
+    NativeModStatus::Failed(reason)
+}
+
+/// Logs how every native mod did, so a failed startup can be traced back to a plugin.
+fn report() {
+    for (name, status) in status() {
+        match status {
+            NativeModStatus::Loaded => log!("Native mod {}: loaded", name),
+            NativeModStatus::Failed(reason) => log!("Native mod {}: failed, {}", name, reason),
+        }
+    }
+}
+
+/// Every native mod that was loaded, and how it's doing.
+pub fn status() -> Vec<(String, NativeModStatus)> {
+    NATIVE_MODS
+        .lock()
+        .map(|mods| mods.iter().map(|m| (m.library.name.clone(), m.status.clone())).collect())
+        .unwrap_or_default()
+}
@@ -0,0 +1,169 @@
+//! Tracks how far startup got, so a failure can be pinned to the exact stage it happened in.
+
+use std::sync::RwLock;
+
+use chrono::{DateTime, Local};
+use lazy_static::lazy_static;
+
+use crate::{debug, error, errors::phaseerr::PhaseError, utils::profiler, warn};
+
+/// The stages of startup, in the order they happen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum LoaderPhase {
+    NotStarted,
+    ConsoleInit,
+    InitHooked,
+    RuntimeInitialized,
+    HostLoaded,
+    PreStarted,
+    Started,
+    Failed,
+}
+
+impl LoaderPhase {
+    /// The phase that is expected to follow this one, if any.
+    pub fn next(self) -> Option<LoaderPhase> {
+        match self {
+            LoaderPhase::NotStarted => Some(LoaderPhase::ConsoleInit),
+            LoaderPhase::ConsoleInit => Some(LoaderPhase::InitHooked),
+            LoaderPhase::InitHooked => Some(LoaderPhase::RuntimeInitialized),
+            LoaderPhase::RuntimeInitialized => Some(LoaderPhase::HostLoaded),
+            LoaderPhase::HostLoaded => Some(LoaderPhase::PreStarted),
+            LoaderPhase::PreStarted => Some(LoaderPhase::Started),
+            LoaderPhase::Started | LoaderPhase::Failed => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PhaseRecord {
+    pub phase: LoaderPhase,
+    pub timestamp: DateTime<Local>,
+}
+
+#[derive(Debug, Clone)]
+pub struct PhaseFailure {
+    /// the phase we were trying to reach when things went wrong
+    pub phase: LoaderPhase,
+    pub reason: String,
+    pub timestamp: DateTime<Local>,
+}
+
+#[derive(Debug)]
+struct PhaseState {
+    current: LoaderPhase,
+    history: Vec<PhaseRecord>,
+    failure: Option<PhaseFailure>,
+}
+
+/// Called after every successful transition with the phase that was reached, and with `Failed` if startup fails.
+pub type PhaseCallback = fn(LoaderPhase);
+
+lazy_static! {
+    static ref SUBSCRIBERS: RwLock<Vec<PhaseCallback>> = RwLock::new(Vec::new());
+    static ref STATE: RwLock<PhaseState> = RwLock::new(PhaseState {
+        current: LoaderPhase::NotStarted,
+        history: vec![PhaseRecord {
+            phase: LoaderPhase::NotStarted,
+            timestamp: Local::now(),
+        }],
+        failure: None,
+    });
+}
+
+pub fn current() -> LoaderPhase {
+    STATE.read().map(|state| state.current).unwrap_or(LoaderPhase::Failed)
+}
+
+/// Moves to the next phase, phases can't be skipped or revisited.
+pub fn transition(next: LoaderPhase) -> Result<(), PhaseError> {
+    {
+        let mut state = STATE.write().map_err(|_| PhaseError::InvalidTransition(LoaderPhase::Failed, next))?;
+
+        if state.current.next() != Some(next) {
+            return Err(PhaseError::InvalidTransition(state.current, next));
+        }
+
+        state.current = next;
+        state.history.push(PhaseRecord {
+            phase: next,
+            timestamp: Local::now(),
+        });
+    }
+
+    let _ = debug!("Loader phase: {:?}", next);
+
+    notify(next);
+
+    Ok(())
+}
+
+fn notify(phase: LoaderPhase) {
+    // copied out so subscribers can subscribe or transition themselves
+    let subscribers = SUBSCRIBERS.read().map(|s| s.clone()).unwrap_or_default();
+    for subscriber in subscribers {
+        subscriber(phase);
+    }
+}
+
+/// Runs `callback` on every phase reached from now on.
+pub fn subscribe(callback: PhaseCallback) {
+    if let Ok(mut subscribers) = SUBSCRIBERS.write() {
+        subscribers.push(callback);
+    }
+}
+
+/// Marks startup as failed, remembering which phase it failed to reach and why.
+pub fn fail(reason: &str) {
+    let failed_phase = {
+        let Ok(mut state) = STATE.write() else { return };
+
+        if state.current == LoaderPhase::Failed {
+            return;
+        }
+
+        let failed_phase = state.current.next().unwrap_or(state.current);
+        let timestamp = Local::now();
+
+        state.current = LoaderPhase::Failed;
+        state.history.push(PhaseRecord {
+            phase: LoaderPhase::Failed,
+            timestamp,
+        });
+        state.failure = Some(PhaseFailure {
+            phase: failed_phase,
+            reason: reason.to_string(),
+            timestamp,
+        });
+
+        failed_phase
+    };
+
+    let _ = error!("Startup failed during loader phase {:?}", failed_phase);
+
+    if let Err(e) = profiler::write_report() {
+        let _ = warn!("Failed to write the startup timing report: {}", e.to_string());
+    }
+
+    notify(LoaderPhase::Failed);
+}
+
+pub fn history() -> Vec<PhaseRecord> {
+    STATE.read().map(|state| state.history.clone()).unwrap_or_default()
+}
+
+pub fn failure() -> Option<PhaseFailure> {
+    STATE.read().ok().and_then(|state| state.failure.clone())
+}
+
+#[no_mangle]
+pub extern "C" fn melonloader_get_loader_phase() -> u8 {
+    current() as u8
+}
+
+/// Returns the phase that failed, or `NotStarted` if nothing has failed.
+#[no_mangle]
+pub extern "C" fn melonloader_get_failed_phase() -> u8 {
+    failure().map(|f| f.phase).unwrap_or(LoaderPhase::NotStarted) as u8
+}
@@ -0,0 +1,50 @@
+//! Lets the game keep running vanilla when MelonLoader fails to start, instead of taking it down with us.
+//!
+//! This is opt-in, through `"safeMode": true` in `UserData/Bootstrap.json` or `--melonloader.safemode`.
+
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    RwLock,
+};
+
+use lazy_static::lazy_static;
+
+use crate::{hooks, phase, safe_mode_enabled, warn};
+
+static ENGAGED: AtomicBool = AtomicBool::new(false);
+
+lazy_static! {
+    static ref REASON: RwLock<Option<String>> = RwLock::new(None);
+}
+
+pub fn is_enabled() -> bool {
+    safe_mode_enabled!()
+}
+
+pub fn is_engaged() -> bool {
+    ENGAGED.load(Ordering::SeqCst)
+}
+
+/// Why safe mode was engaged, if it was.
+pub fn reason() -> Option<String> {
+    REASON.read().ok().and_then(|reason| reason.clone())
+}
+
+/// Records the reason, marks startup as failed and detaches every hook.
+///
+/// Any loader stage that runs after this is skipped.
+pub fn engage(reason: &str) {
+    if ENGAGED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+
+    if let Ok(mut stored) = REASON.write() {
+        *stored = Some(reason.to_string());
+    }
+
+    phase::fail(reason);
+
+    let _ = warn!("Safe mode engaged, the game will continue without MelonLoader");
+
+    hooks::detach_all();
+}
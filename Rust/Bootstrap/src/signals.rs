@@ -0,0 +1,116 @@
+//! Routes SIGTERM and SIGINT into the regular shutdown sequence.
+//!
+//! Almost nothing is safe to do inside a signal handler, so the handler only writes the signal
+//! number into a pipe, and a watcher thread runs the shutdown sequence once something arrives.
+//! The handlers that were installed before ours are put back afterwards and the signal is raised
+//! again, so the game, the runtime, or the default action still get the final say.
+
+use std::{
+    collections::HashMap,
+    ffi::{c_int, c_void},
+    ptr::null_mut,
+    sync::{
+        atomic::{AtomicI32, Ordering},
+        Mutex,
+    },
+};
+
+use lazy_static::lazy_static;
+
+use crate::{
+    core, debug,
+    errors::{signalerr::SignalError, DynErr},
+    log,
+};
+
+const SIGNALS: [c_int; 2] = [libc::SIGTERM, libc::SIGINT];
+
+static PIPE_WRITE: AtomicI32 = AtomicI32::new(-1);
+
+lazy_static! {
+    /// the actions our handlers replaced, keyed by signal
+    static ref PREVIOUS: Mutex<HashMap<c_int, libc::sigaction>> = Mutex::new(HashMap::new());
+}
+
+pub fn init() -> Result<(), DynErr> {
+    let mut fds: [c_int; 2] = [-1; 2];
+    if unsafe { libc::pipe(fds.as_mut_ptr()) } != 0 {
+        return Err(SignalError::FailedToCreatePipe.into());
+    }
+
+    let [read_fd, write_fd] = fds;
+    PIPE_WRITE.store(write_fd, Ordering::SeqCst);
+
+    std::thread::Builder::new()
+        .name("MelonLoader Signals".to_string())
+        .spawn(move || watch(read_fd))?;
+
+    for signal in SIGNALS {
+        let mut previous: libc::sigaction = unsafe { std::mem::zeroed() };
+        if unsafe { libc::sigaction(signal, null_mut(), &mut previous) } != 0 {
+            return Err(SignalError::FailedToInstallHandler(signal).into());
+        }
+
+        // whoever launched us asked for this signal to be ignored, so it shouldn't shut us down either
+        if previous.sa_sigaction == libc::SIG_IGN {
+            continue;
+        }
+
+        let mut action: libc::sigaction = unsafe { std::mem::zeroed() };
+        action.sa_sigaction = on_signal as extern "C" fn(c_int) as libc::sighandler_t;
+        action.sa_flags = libc::SA_RESTART;
+
+        if unsafe { libc::sigaction(signal, &action, null_mut()) } != 0 {
+            return Err(SignalError::FailedToInstallHandler(signal).into());
+        }
+
+        PREVIOUS.lock().map_err(|_| SignalError::FailedToInstallHandler(signal))?.insert(signal, previous);
+    }
+
+    debug!("Installed shutdown signal handlers")?;
+
+    Ok(())
+}
+
+extern "C" fn on_signal(signal: c_int) {
+    let byte = signal as u8;
+    unsafe {
+        libc::write(
+            PIPE_WRITE.load(Ordering::Relaxed),
+            &byte as *const u8 as *const c_void,
+            1,
+        );
+    }
+}
+
+fn watch(read_fd: c_int) {
+    let mut signal = 0u8;
+
+    loop {
+        let read = unsafe { libc::read(read_fd, &mut signal as *mut u8 as *mut c_void, 1) };
+
+        match read {
+            1 => break,
+            -1 if std::io::Error::last_os_error().kind() == std::io::ErrorKind::Interrupted => {}
+            _ => return,
+        }
+    }
+
+    log!("Received signal {}, shutting down", signal);
+
+    if !core::prepare_shutdown() {
+        return;
+    }
+
+    let signal = signal as c_int;
+    let previous = PREVIOUS.lock().ok().and_then(|previous| previous.get(&signal).copied());
+
+    match previous {
+        // hands the signal to whatever handled it before us, the default action ends the process
+        Some(previous) => unsafe {
+            libc::sigaction(signal, &previous, null_mut());
+            libc::raise(signal);
+        },
+        None => std::process::exit(0),
+    }
+}
@@ -0,0 +1,319 @@
+//! Finds hostfxr and the shared framework inside the dotnet root.
+//!
+//! The framework is picked the same way the muxer would, following the roll forward policy
+//! from `MelonLoader.runtimeconfig.json`, so a runtime update doesn't need a Bootstrap rebuild.
+
+use std::{cmp::Ordering, fmt, path::{Path, PathBuf}};
+
+use crate::{
+    debug,
+    errors::{dotneterr::DotnetErr, DynErr},
+    melonenv::paths,
+    utils::runtime_config::{self, RuntimeConfig},
+};
+
+#[cfg(windows)]
+const HOSTFXR_NAME: &str = "hostfxr.dll";
+#[cfg(not(windows))]
+const HOSTFXR_NAME: &str = "libhostfxr.so";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DotnetVersion {
+    pub major: u32,
+    pub minor: u32,
+    pub patch: u32,
+    pub prerelease: Option<String>,
+}
+
+impl DotnetVersion {
+    pub fn parse(version: &str) -> Option<Self> {
+        // build metadata never affects precedence
+        let version = version.split('+').next()?;
+        let (numbers, prerelease) = match version.split_once('-') {
+            Some((numbers, pre)) => (numbers, Some(pre.to_string())),
+            None => (version, None),
+        };
+
+        let mut parts = numbers.split('.');
+        let major = parts.next()?.parse().ok()?;
+        let minor = parts.next()?.parse().ok()?;
+        let patch = parts.next().unwrap_or("0").parse().ok()?;
+
+        if parts.next().is_some() {
+            return None;
+        }
+
+        Some(Self { major, minor, patch, prerelease })
+    }
+
+    pub fn is_prerelease(&self) -> bool {
+        self.prerelease.is_some()
+    }
+
+    fn same_feature_band(&self, other: &Self) -> bool {
+        self.major == other.major && self.minor == other.minor
+    }
+}
+
+impl Ord for DotnetVersion {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.major, self.minor, self.patch)
+            .cmp(&(other.major, other.minor, other.patch))
+            .then_with(|| match (&self.prerelease, &other.prerelease) {
+                (None, None) => Ordering::Equal,
+                (None, Some(_)) => Ordering::Greater,
+                (Some(_), None) => Ordering::Less,
+                (Some(a), Some(b)) => compare_prerelease(a, b),
+            })
+    }
+}
+
+/// SemVer precedence: dot separated identifiers compared in order, numeric ones numerically,
+/// numeric ones lower than alphanumeric ones, and a shorter list lower when it's a prefix of the other.
+fn compare_prerelease(a: &str, b: &str) -> Ordering {
+    let mut a = a.split('.');
+    let mut b = b.split('.');
+
+    loop {
+        let ordering = match (a.next(), b.next()) {
+            (None, None) => return Ordering::Equal,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(a), Some(b)) => match (a.parse::<u64>(), b.parse::<u64>()) {
+                (Ok(a), Ok(b)) => a.cmp(&b),
+                (Ok(_), Err(_)) => Ordering::Less,
+                (Err(_), Ok(_)) => Ordering::Greater,
+                (Err(_), Err(_)) => a.cmp(b),
+            },
+        };
+
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+}
+
+impl PartialOrd for DotnetVersion {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for DotnetVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)?;
+        if let Some(pre) = &self.prerelease {
+            write!(f, "-{pre}")?;
+        }
+        Ok(())
+    }
+}
+
+/// https://learn.microsoft.com/dotnet/core/versions/selection#framework-dependent-apps-roll-forward
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RollForward {
+    Disable,
+    LatestPatch,
+    #[default]
+    Minor,
+    LatestMinor,
+    Major,
+    LatestMajor,
+}
+
+impl RollForward {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_ascii_lowercase().as_str() {
+            "disable" => Some(Self::Disable),
+            "latestpatch" => Some(Self::LatestPatch),
+            "minor" => Some(Self::Minor),
+            "latestminor" => Some(Self::LatestMinor),
+            "major" => Some(Self::Major),
+            "latestmajor" => Some(Self::LatestMajor),
+            _ => None,
+        }
+    }
+
+    /// Picks the version to run from `available`, or `None` if the policy allows none of them.
+    /// Pre-release versions are only considered when a pre-release was requested.
+    pub fn select<'a>(
+        self,
+        requested: &DotnetVersion,
+        available: &'a [DotnetVersion],
+    ) -> Option<&'a DotnetVersion> {
+        let candidates = available
+            .iter()
+            .filter(|v| *v >= requested)
+            .filter(|v| requested.is_prerelease() || !v.is_prerelease());
+
+        match self {
+            Self::Disable => candidates.into_iter().find(|v| *v == requested),
+            Self::LatestPatch => candidates.filter(|v| v.same_feature_band(requested)).max(),
+            Self::Minor => {
+                latest_patch_of_lowest_band(candidates.filter(|v| v.major == requested.major))
+            }
+            Self::LatestMinor => candidates.filter(|v| v.major == requested.major).max(),
+            Self::Major => latest_patch_of_lowest_band(candidates),
+            Self::LatestMajor => candidates.max(),
+        }
+    }
+}
+
+impl fmt::Display for RollForward {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self, f)
+    }
+}
+
+fn latest_patch_of_lowest_band<'a>(
+    candidates: impl Iterator<Item = &'a DotnetVersion>,
+) -> Option<&'a DotnetVersion> {
+    let candidates: Vec<&DotnetVersion> = candidates.collect();
+    let lowest = candidates.iter().min()?;
+
+    candidates
+        .iter()
+        .filter(|v| v.same_feature_band(lowest))
+        .max()
+        .copied()
+}
+
+/// Every version folder directly inside `dir`, highest first. Anything that isn't a version is skipped.
+pub fn installed_versions(dir: &Path) -> Vec<DotnetVersion> {
+    let Ok(entries) = std::fs::read_dir(dir) else { return Vec::new() };
+
+    let mut versions: Vec<DotnetVersion> = entries
+        .filter_map(Result::ok)
+        .filter(|e| e.path().is_dir())
+        .filter_map(|e| DotnetVersion::parse(&e.file_name().to_string_lossy()))
+        .collect();
+
+    versions.sort_by(|a, b| b.cmp(a));
+    versions
+}
+
+/// hostfxr is backwards compatible, so the newest installed one is always the right choice.
+pub fn hostfxr_path() -> Result<PathBuf, DynErr> {
+    let fxr_dir = paths::get_dotnet_path()?.join("host").join("fxr");
+
+    let path = installed_versions(&fxr_dir)
+        .into_iter()
+        .map(|v| fxr_dir.join(v.to_string()).join(HOSTFXR_NAME))
+        .find(|p| p.exists())
+        .ok_or_else(|| DotnetErr::NoHostFxr(fxr_dir.clone()))?;
+
+    debug!("[Dotnet] Using hostfxr at {}", path.display())?;
+
+    Ok(path)
+}
+
+/// The shared framework folder `MelonLoader.runtimeconfig.json` resolves to.
+pub fn framework_dir() -> Result<PathBuf, DynErr> {
+    let config = RuntimeConfig::load(&runtime_config::path()?)?;
+    resolve_framework(&config)
+}
+
+/// Resolves the runtime framework of an already loaded config, following its roll forward policy.
+pub fn resolve_framework(config: &RuntimeConfig) -> Result<PathBuf, DynErr> {
+    let framework = config.runtime_framework()?;
+    let roll_forward = config.roll_forward(framework)?;
+
+    let requested = DotnetVersion::parse(&framework.version).ok_or_else(|| {
+        DotnetErr::InvalidFrameworkVersion {
+            name: framework.name.clone(),
+            version: framework.version.clone(),
+        }
+    })?;
+
+    let shared_dir = paths::get_dotnet_path()?.join("shared").join(&framework.name);
+    let available = installed_versions(&shared_dir);
+
+    let selected = roll_forward
+        .select(&requested, &available)
+        .ok_or_else(|| DotnetErr::NoCompatibleFramework {
+            name: framework.name.clone(),
+            requested: requested.to_string(),
+            roll_forward,
+            available: available
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(", "),
+        })?;
+
+    debug!(
+        "[Dotnet] Resolved {} {} to {} (roll forward: {})",
+        framework.name, requested, selected, roll_forward
+    )?;
+
+    Ok(shared_dir.join(selected.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn v(version: &str) -> DotnetVersion {
+        DotnetVersion::parse(version).unwrap()
+    }
+
+    fn versions(versions: &[&str]) -> Vec<DotnetVersion> {
+        versions.iter().map(|s| v(s)).collect()
+    }
+
+    #[test]
+    fn parses_versions() {
+        assert_eq!(
+            v("8.0.11"),
+            DotnetVersion { major: 8, minor: 0, patch: 11, prerelease: None }
+        );
+        assert_eq!(v("6.0"), v("6.0.0"));
+        assert_eq!(v("9.0.0-rc.2.24473.5").prerelease.as_deref(), Some("rc.2.24473.5"));
+        assert_eq!(v("8.0.1+abcdef"), v("8.0.1"));
+        assert_eq!(v("9.0.0-preview.1+build.7").prerelease.as_deref(), Some("preview.1"));
+
+        assert!(DotnetVersion::parse("8").is_none());
+        assert!(DotnetVersion::parse("8.0.1.2").is_none());
+        assert!(DotnetVersion::parse("8.x.1").is_none());
+        assert!(DotnetVersion::parse("").is_none());
+    }
+
+    #[test]
+    fn orders_prereleases_by_semver() {
+        assert!(v("9.0.0-rc.1") < v("9.0.0"));
+        assert!(v("9.0.0-preview.7") < v("9.0.0-rc.1"));
+        assert!(v("9.0.0-rc.2") < v("9.0.0-rc.10"));
+        assert!(v("9.0.0-rc.2.24473.5") < v("9.0.0-rc.2.24474.1"));
+        assert!(v("9.0.0-rc") < v("9.0.0-rc.1"));
+        assert!(v("9.0.0-1") < v("9.0.0-alpha"));
+        assert_eq!(v("9.0.0-rc.1").cmp(&v("9.0.0-rc.1")), Ordering::Equal);
+    }
+
+    #[test]
+    fn selects_by_roll_forward() {
+        let available = versions(&["6.0.5", "6.0.36", "6.1.2", "7.0.1", "7.0.20", "8.0.11", "9.0.0-rc.2"]);
+        let select = |policy: RollForward, requested: &str| {
+            policy.select(&v(requested), &available).map(ToString::to_string)
+        };
+
+        assert_eq!(select(RollForward::Disable, "6.0.5").as_deref(), Some("6.0.5"));
+        assert_eq!(select(RollForward::Disable, "6.0.6"), None);
+        assert_eq!(select(RollForward::LatestPatch, "6.0.0").as_deref(), Some("6.0.36"));
+        assert_eq!(select(RollForward::LatestPatch, "6.2.0"), None);
+        assert_eq!(select(RollForward::Minor, "6.0.0").as_deref(), Some("6.0.36"));
+        assert_eq!(select(RollForward::Minor, "6.0.37").as_deref(), Some("6.1.2"));
+        assert_eq!(select(RollForward::LatestMinor, "6.0.0").as_deref(), Some("6.1.2"));
+        assert_eq!(select(RollForward::Major, "6.2.0").as_deref(), Some("7.0.20"));
+        assert_eq!(select(RollForward::LatestMajor, "6.0.0").as_deref(), Some("8.0.11"));
+        assert_eq!(select(RollForward::LatestMajor, "10.0.0"), None);
+    }
+
+    #[test]
+    fn only_selects_prereleases_when_requested() {
+        let available = versions(&["8.0.11", "9.0.0-rc.1", "9.0.0-rc.2"]);
+
+        assert_eq!(RollForward::LatestMajor.select(&v("8.0.0"), &available), Some(&v("8.0.11")));
+        assert_eq!(RollForward::Major.select(&v("9.0.0-rc.1"), &available), Some(&v("9.0.0-rc.2")));
+        assert_eq!(RollForward::Disable.select(&v("9.0.0-rc.1"), &available), Some(&v("9.0.0-rc.1")));
+    }
+}
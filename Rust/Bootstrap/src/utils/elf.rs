@@ -0,0 +1,135 @@
+//! ELF structures and constants, sized for the pointer width we're built for.
+
+#![allow(non_camel_case_types)]
+
+pub const DT_NULL: isize = 0;
+pub const DT_PLTRELSZ: isize = 2;
+pub const DT_STRTAB: isize = 5;
+pub const DT_SYMTAB: isize = 6;
+pub const DT_RELA: isize = 7;
+pub const DT_RELASZ: isize = 8;
+pub const DT_REL: isize = 17;
+pub const DT_RELSZ: isize = 18;
+pub const DT_PLTREL: isize = 20;
+pub const DT_JMPREL: isize = 23;
+
+pub const ELF_MAGIC: [u8; 4] = *b"\x7fELF";
+pub const EI_CLASS: usize = 4;
+#[cfg(target_pointer_width = "64")]
+pub const ELF_CLASS: u8 = 2; // ELFCLASS64
+#[cfg(target_pointer_width = "32")]
+pub const ELF_CLASS: u8 = 1; // ELFCLASS32
+
+pub const PT_LOAD: u32 = 1;
+pub const SHT_SYMTAB: u32 = 2;
+pub const SHT_DYNSYM: u32 = 11;
+pub const SHN_UNDEF: u16 = 0;
+
+/// Relocation types that fill a GOT slot with a symbol's address.
+/// Absolute relocations are left out, they can point into data and don't always land in the GOT.
+#[cfg(target_arch = "x86_64")]
+pub const GOT_RELOCATIONS: [u32; 2] = [6, 7]; // R_X86_64_GLOB_DAT, JUMP_SLOT
+#[cfg(target_arch = "x86")]
+pub const GOT_RELOCATIONS: [u32; 2] = [6, 7]; // R_386_GLOB_DAT, JMP_SLOT
+#[cfg(target_arch = "aarch64")]
+pub const GOT_RELOCATIONS: [u32; 2] = [1025, 1026]; // R_AARCH64_GLOB_DAT, JUMP_SLOT
+#[cfg(target_arch = "arm")]
+pub const GOT_RELOCATIONS: [u32; 2] = [21, 22]; // R_ARM_GLOB_DAT, JUMP_SLOT
+
+#[cfg(target_pointer_width = "64")]
+mod types {
+    pub type Ehdr = libc::Elf64_Ehdr;
+    pub type Phdr = libc::Elf64_Phdr;
+    pub type Shdr = libc::Elf64_Shdr;
+
+    #[repr(C)]
+    #[derive(Debug, Clone, Copy)]
+    pub struct Dyn {
+        pub d_tag: i64,
+        pub d_val: u64,
+    }
+
+    #[repr(C)]
+    #[derive(Debug, Clone, Copy)]
+    pub struct Sym {
+        pub st_name: u32,
+        pub st_info: u8,
+        pub st_other: u8,
+        pub st_shndx: u16,
+        pub st_value: u64,
+        pub st_size: u64,
+    }
+
+    #[repr(C)]
+    #[derive(Debug, Clone, Copy)]
+    pub struct Rela {
+        pub r_offset: u64,
+        pub r_info: u64,
+        pub r_addend: i64,
+    }
+
+    #[repr(C)]
+    #[derive(Debug, Clone, Copy)]
+    pub struct Rel {
+        pub r_offset: u64,
+        pub r_info: u64,
+    }
+
+    pub fn r_sym(info: u64) -> usize {
+        (info >> 32) as usize
+    }
+
+    pub fn r_type(info: u64) -> u32 {
+        (info & 0xFFFF_FFFF) as u32
+    }
+}
+
+#[cfg(target_pointer_width = "32")]
+mod types {
+    pub type Ehdr = libc::Elf32_Ehdr;
+    pub type Phdr = libc::Elf32_Phdr;
+    pub type Shdr = libc::Elf32_Shdr;
+
+    #[repr(C)]
+    #[derive(Debug, Clone, Copy)]
+    pub struct Dyn {
+        pub d_tag: i32,
+        pub d_val: u32,
+    }
+
+    #[repr(C)]
+    #[derive(Debug, Clone, Copy)]
+    pub struct Sym {
+        pub st_name: u32,
+        pub st_value: u32,
+        pub st_size: u32,
+        pub st_info: u8,
+        pub st_other: u8,
+        pub st_shndx: u16,
+    }
+
+    #[repr(C)]
+    #[derive(Debug, Clone, Copy)]
+    pub struct Rela {
+        pub r_offset: u32,
+        pub r_info: u32,
+        pub r_addend: i32,
+    }
+
+    #[repr(C)]
+    #[derive(Debug, Clone, Copy)]
+    pub struct Rel {
+        pub r_offset: u32,
+        pub r_info: u32,
+    }
+
+    pub fn r_sym(info: u32) -> usize {
+        (info >> 8) as usize
+    }
+
+    pub fn r_type(info: u32) -> u32 {
+        info & 0xFF
+    }
+}
+
+pub use types::*;
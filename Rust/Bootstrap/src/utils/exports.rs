@@ -0,0 +1,222 @@
+//! Looks up runtime exports by their canonical name, honouring the per-game `UserData/ExportMap.json`.
+//!
+//! Some games ship an IL2CPP or Mono runtime with renamed exports. The map points a canonical name at the
+//! game's name for it, or at a signature to scan for when it isn't exported at all:
+//!
+//! ```json
+//! {
+//!     "il2cpp_init": "x9f2_init",
+//!     "il2cpp_runtime_invoke": { "pattern": "FF 43 01 D1 ?? ?? ?? ??", "module": "libil2cpp.so", "offset": 0 }
+//! }
+//! ```
+//!
+//! Names that aren't in the map are looked up as is. Every runtime function the Bootstrap calls itself,
+//! including method names in the invoke hook and internal call registration, is resolved through here.
+//! Only the helpers unity_rs uses internally still look up the literal names.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    ffi::{c_char, c_void, CStr, CString},
+    path::PathBuf,
+    sync::Mutex,
+};
+
+use lazy_static::lazy_static;
+use serde::Deserialize;
+use unity_rs::{
+    common::method::MethodPointer,
+    runtime::{FerrexRuntime, RuntimeType},
+};
+
+use crate::{
+    debug,
+    errors::{exporterr::ExportError, DynErr},
+    melonenv::paths,
+    utils::{modules, sigscan},
+    warn,
+};
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum ExportSource {
+    /// the name the game exports it under
+    Name(String),
+    /// a signature to scan `module` for, the function starts `offset` bytes from the match
+    Pattern {
+        pattern: String,
+        module: Option<String>,
+        #[serde(default)]
+        offset: isize,
+    },
+}
+
+/// Everything the Bootstrap resolves itself on IL2CPP games.
+pub const IL2CPP_EXPORTS: &[&str] = &[
+    "il2cpp_init",
+    "il2cpp_runtime_invoke",
+    "il2cpp_method_get_name",
+    "il2cpp_method_get_class",
+    "il2cpp_class_get_name",
+    "il2cpp_class_get_namespace",
+    "il2cpp_get_corlib",
+    "il2cpp_domain_get",
+    "il2cpp_thread_attach",
+    #[cfg(target_os = "android")]
+    "il2cpp_thread_get_all_attached_threads",
+];
+
+/// Everything the Bootstrap resolves itself on Mono games.
+pub const MONO_EXPORTS: &[&str] = &[
+    "mono_jit_init_version",
+    "mono_runtime_invoke",
+    "mono_method_get_name",
+    "mono_method_get_class",
+    "mono_class_get_name",
+    "mono_class_get_namespace",
+    "mono_thread_current",
+    "mono_thread_set_main",
+    "mono_get_root_domain",
+    "mono_thread_attach",
+    "mono_add_internal_call",
+];
+
+lazy_static! {
+    static ref EXPORT_MAP: BTreeMap<String, ExportSource> = load().unwrap_or_else(|e| {
+        let _ = warn!("Failed to read {}: {}, ignoring it", map_path().display(), e.to_string());
+        BTreeMap::new()
+    });
+
+    /// resolved addresses, some of these are looked up on every invoke
+    static ref RESOLVED: Mutex<HashMap<String, usize>> = Mutex::new(HashMap::new());
+}
+
+pub fn map_path() -> PathBuf {
+    paths::USER_DATA_FOLDER.join("ExportMap.json")
+}
+
+fn load() -> Result<BTreeMap<String, ExportSource>, DynErr> {
+    let path = map_path();
+    if !path.exists() {
+        return Ok(BTreeMap::new());
+    }
+
+    let contents = std::fs::read_to_string(path)?;
+    Ok(serde_json::from_str(&contents)?)
+}
+
+/// Resolves the export known as `canonical`, like `il2cpp_init`.
+pub fn resolve(runtime: &FerrexRuntime, canonical: &str) -> Result<*mut c_void, DynErr> {
+    if let Some(address) = RESOLVED.lock().ok().and_then(|r| r.get(canonical).copied()) {
+        return Ok(address as *mut c_void);
+    }
+
+    let address = lookup(runtime, canonical).map_err(|e| {
+        let _ = debug!("Failed to resolve {}: {}", canonical, e.to_string());
+        ExportError::Unresolved(vec![canonical.to_string()])
+    })?;
+
+    if let Ok(mut resolved) = RESOLVED.lock() {
+        resolved.insert(canonical.to_string(), address as usize);
+    }
+
+    Ok(address)
+}
+
+/// The name of a runtime method, through `il2cpp_method_get_name` or `mono_method_get_name`.
+pub fn method_name(runtime: &FerrexRuntime, method: *mut c_void) -> Result<String, DynErr> {
+    let canonical = match runtime.get_type() {
+        RuntimeType::Mono(_) => "mono_method_get_name",
+        RuntimeType::Il2Cpp(_) => "il2cpp_method_get_name",
+    };
+
+    let get_name = unsafe { std::mem::transmute::<*mut c_void, fn(*mut c_void) -> *const c_char>(resolve(runtime, canonical)?) };
+
+    let name = get_name(method);
+    if name.is_null() {
+        return Err("Method has no name".into());
+    }
+
+    Ok(unsafe { CStr::from_ptr(name) }.to_string_lossy().into_owned())
+}
+
+/// Registers `method` as the internal call `name`, like `MelonLoader.BootstrapInterop::NativeHookAttach`.
+pub fn add_internal_call(runtime: &FerrexRuntime, name: &str, method: MethodPointer) -> Result<(), DynErr> {
+    let canonical = match runtime.get_type() {
+        RuntimeType::Mono(_) => "mono_add_internal_call",
+        RuntimeType::Il2Cpp(_) => "il2cpp_add_internal_call",
+    };
+
+    let add = unsafe { std::mem::transmute::<*mut c_void, fn(*const c_char, MethodPointer)>(resolve(runtime, canonical)?) };
+
+    // internal calls live as long as the runtime, so the name does too
+    let name = CString::new(name)?;
+    add(name.into_raw(), method);
+
+    Ok(())
+}
+
+/// Makes sure every export the Bootstrap needs for this runtime resolves, naming all the ones that don't.
+pub fn check(runtime: &FerrexRuntime) -> Result<(), DynErr> {
+    let required = match runtime.get_type() {
+        RuntimeType::Mono(_) => MONO_EXPORTS,
+        RuntimeType::Il2Cpp(_) => IL2CPP_EXPORTS,
+    };
+
+    let missing: Vec<String> = required
+        .iter()
+        .filter(|name| resolve(runtime, name).is_err())
+        .map(|name| name.to_string())
+        .collect();
+
+    if !missing.is_empty() {
+        return Err(ExportError::Unresolved(missing).into());
+    }
+
+    Ok(())
+}
+
+fn lookup(runtime: &FerrexRuntime, canonical: &str) -> Result<*mut c_void, DynErr> {
+    let source = EXPORT_MAP.get(canonical);
+
+    let address = match source {
+        None => runtime.get_export_ptr(canonical)?,
+        Some(ExportSource::Name(name)) => runtime.get_export_ptr(name)?,
+        Some(ExportSource::Pattern { pattern, module, offset }) => {
+            let module = match module {
+                Some(module) => module.clone(),
+                None => runtime_module(runtime).ok_or_else(|| ExportError::NoModule(canonical.to_string()))?,
+            };
+
+            let matches = sigscan::scan_module(&module, pattern)?;
+            let [found] = matches.as_slice() else {
+                return Err(ExportError::AmbiguousPattern {
+                    name: canonical.to_string(),
+                    count: matches.len(),
+                }
+                .into());
+            };
+
+            found.wrapping_add_signed(*offset) as *mut c_void
+        }
+    };
+
+    if source.is_some() {
+        debug!("Resolved {} through the export map at {:#x}", canonical, address as usize)?;
+    }
+
+    Ok(address)
+}
+
+/// Guesses which loaded module is the runtime, for patterns that don't name one.
+fn runtime_module(runtime: &FerrexRuntime) -> Option<String> {
+    let candidates: &[&str] = match runtime.get_type() {
+        RuntimeType::Il2Cpp(_) => &["libil2cpp.so", "GameAssembly.so"],
+        RuntimeType::Mono(_) => &["libmonobdwgc-2.0.so", "libmono.so", "libmonosgen-2.0.so"],
+    };
+
+    let loaded = modules::all().ok()?;
+    candidates
+        .iter()
+        .find(|name| loaded.iter().any(|m| m.name == **name))
+        .map(|name| name.to_string())
+}
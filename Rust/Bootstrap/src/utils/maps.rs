@@ -0,0 +1,78 @@
+//! Parses `/proc/self/maps`, the kernel's view of what is mapped into our process.
+
+use std::path::Path;
+
+use crate::errors::DynErr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Permissions {
+    pub read: bool,
+    pub write: bool,
+    pub execute: bool,
+    pub private: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct MapEntry {
+    pub start: usize,
+    pub end: usize,
+    pub perms: Permissions,
+    pub offset: usize,
+    /// the backing file, or a pseudo name like `[stack]`, empty for anonymous mappings
+    pub path: String,
+}
+
+impl MapEntry {
+    pub fn size(&self) -> usize {
+        self.end - self.start
+    }
+
+    pub fn contains(&self, address: usize) -> bool {
+        (self.start..self.end).contains(&address)
+    }
+
+    /// The file name of the backing file, if there is one.
+    pub fn file_name(&self) -> Option<&str> {
+        if !self.path.starts_with('/') {
+            return None;
+        }
+
+        Path::new(&self.path).file_name()?.to_str()
+    }
+}
+
+pub fn read() -> Result<Vec<MapEntry>, DynErr> {
+    let maps = std::fs::read_to_string("/proc/self/maps")?;
+    Ok(maps.lines().filter_map(parse_line).collect())
+}
+
+/// `7f3c1a000000-7f3c1a021000 r-xp 00000000 fd:01 1234   /usr/lib/libc.so.6`
+pub fn parse_line(line: &str) -> Option<MapEntry> {
+    let mut fields = line.split_whitespace();
+
+    let (start, end) = fields.next()?.split_once('-')?;
+    let perms = fields.next()?.as_bytes();
+    let offset = fields.next()?;
+    let _device = fields.next()?;
+    let _inode = fields.next()?;
+
+    if perms.len() < 4 {
+        return None;
+    }
+
+    // paths can contain spaces, so take the rest of the line as is
+    let path = fields.collect::<Vec<_>>().join(" ");
+
+    Some(MapEntry {
+        start: usize::from_str_radix(start, 16).ok()?,
+        end: usize::from_str_radix(end, 16).ok()?,
+        perms: Permissions {
+            read: perms[0] == b'r',
+            write: perms[1] == b'w',
+            execute: perms[2] == b'x',
+            private: perms[3] == b'p',
+        },
+        offset: usize::from_str_radix(offset, 16).ok()?,
+        path,
+    })
+}
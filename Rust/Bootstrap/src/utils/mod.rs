@@ -1,5 +1,14 @@
+pub mod dotnet_versions;
+pub mod elf;
+pub mod exports;
+pub mod maps;
+pub mod modules;
+pub mod profiler;
 pub mod runtime;
+pub mod runtime_config;
+pub mod sigscan;
 pub mod strings;
+pub mod symbols;
 pub mod pathbuf_impls;
 #[cfg(target_os = "android")]
 pub mod apk_asset_manager;
@@ -0,0 +1,358 @@
+//! Groups the segments in `/proc/self/maps` into the modules they belong to.
+
+use std::{
+    ffi::{c_char, CStr, CString},
+    path::Path,
+    sync::RwLock,
+};
+
+use lazy_static::lazy_static;
+
+use crate::{
+    errors::DynErr,
+    utils::{
+        elf,
+        maps::{self, MapEntry, Permissions},
+    },
+};
+
+/// A file mapped into the process, usually a shared library or the executable.
+#[derive(Debug, Clone)]
+pub struct Module {
+    /// the file name, like `libunity.so`, also for libraries loaded straight out of an APK
+    pub name: String,
+    /// the mapped file, which is the APK itself for libraries loaded out of one
+    pub path: String,
+    /// the lowest mapped address, what module offsets are relative to
+    pub base: usize,
+    /// the end of the highest mapped segment
+    pub end: usize,
+    pub segments: Vec<MapEntry>,
+}
+
+impl Module {
+    /// The span from the first to the last segment, including any gaps between them.
+    pub fn size(&self) -> usize {
+        self.end - self.base
+    }
+
+    pub fn contains(&self, address: usize) -> bool {
+        self.segments.iter().any(|s| s.contains(address))
+    }
+
+    /// The permissions of the segment `address` is in.
+    pub fn permissions_at(&self, address: usize) -> Option<Permissions> {
+        self.segments.iter().find(|s| s.contains(address)).map(|s| s.perms)
+    }
+
+    /// Every permission any of the segments has.
+    pub fn permissions(&self) -> Permissions {
+        self.segments.iter().fold(Permissions::default(), |acc, s| Permissions {
+            read: acc.read || s.perms.read,
+            write: acc.write || s.perms.write,
+            execute: acc.execute || s.perms.execute,
+            private: acc.private || s.perms.private,
+        })
+    }
+
+    pub fn executable_segments(&self) -> impl Iterator<Item = &MapEntry> {
+        self.segments.iter().filter(|s| s.perms.read && s.perms.execute)
+    }
+}
+
+/// Groups already read maps by their backing file. Anonymous and pseudo mappings are left out.
+///
+/// Libraries loaded straight out of an APK (`extractNativeLibs=false`) all map `base.apk`, so a mapping
+/// that starts with an ELF header, or goes back in the file, starts a new module even if the path matches.
+pub fn group(maps: &[MapEntry]) -> Vec<Module> {
+    let mut modules: Vec<Module> = Vec::new();
+
+    for entry in maps {
+        let Some(name) = entry.file_name() else {
+            continue;
+        };
+
+        let module = modules.iter_mut().rev().find(|m| m.path == entry.path);
+
+        match module {
+            Some(module) if continues(module, entry) => {
+                module.base = module.base.min(entry.start);
+                module.end = module.end.max(entry.end);
+                module.segments.push(entry.clone());
+            }
+            _ => modules.push(Module {
+                name: embedded_name(entry).unwrap_or_else(|| name.to_string()),
+                path: entry.path.clone(),
+                base: entry.start,
+                end: entry.end,
+                segments: vec![entry.clone()],
+            }),
+        }
+    }
+
+    modules
+}
+
+/// Whether `entry` is another segment of `module`, rather than the start of a different file in the same archive.
+fn continues(module: &Module, entry: &MapEntry) -> bool {
+    let Some(last) = module.segments.last() else {
+        return false;
+    };
+
+    entry.offset >= last.offset && !starts_with_elf_header(entry)
+}
+
+fn starts_with_elf_header(entry: &MapEntry) -> bool {
+    // device mappings can have side effects on read, and nothing in there is an ELF
+    if !entry.perms.read || entry.size() < elf::ELF_MAGIC.len() || entry.path.starts_with("/dev/") {
+        return false;
+    }
+
+    let header = unsafe { std::slice::from_raw_parts(entry.start as *const u8, elf::ELF_MAGIC.len()) };
+    header == elf::ELF_MAGIC
+}
+
+/// The dynamic linker reports libraries loaded out of an APK as `base.apk!/lib/<abi>/libfoo.so`.
+fn embedded_name(entry: &MapEntry) -> Option<String> {
+    if entry.offset == 0 {
+        return None;
+    }
+
+    let mut info: libc::Dl_info = unsafe { std::mem::zeroed() };
+    if unsafe { libc::dladdr(entry.start as *const libc::c_void, &mut info) } == 0 || info.dli_fname.is_null() {
+        return None;
+    }
+
+    let fname = unsafe { CStr::from_ptr(info.dli_fname) }.to_string_lossy();
+    let (_, inner) = fname.split_once("!/")?;
+
+    Some(Path::new(inner).file_name()?.to_string_lossy().into_owned())
+}
+
+/// Every module currently loaded.
+pub fn all() -> Result<Vec<Module>, DynErr> {
+    Ok(group(&maps::read()?))
+}
+
+/// Finds a module by file name (`libunity.so`) or full path.
+pub fn find(name: &str) -> Result<Option<Module>, DynErr> {
+    Ok(all()?.into_iter().find(|m| m.name == name || m.path == name))
+}
+
+/// The module `address` lies in, and its offset from the module's base.
+pub fn resolve(address: usize) -> Result<Option<(Module, usize)>, DynErr> {
+    Ok(all()?
+        .into_iter()
+        .find(|m| m.contains(address))
+        .map(|m| {
+            let offset = address - m.base;
+            (m, offset)
+        }))
+}
+
+/// `libfoo.so+0x1234` for addresses inside a module, otherwise just the address.
+pub fn describe_in(maps: &[MapEntry], address: usize) -> String {
+    if !maps.iter().any(|m| m.contains(address)) {
+        return format!("{address:#x} (unmapped)");
+    }
+
+    match group(maps).into_iter().find(|m| m.contains(address)) {
+        Some(module) => format!("{}+{:#x}", module.name, address - module.base),
+        None => format!("{address:#x}"),
+    }
+}
+
+/// Like `describe_in`, reading the maps itself.
+pub fn describe(address: usize) -> String {
+    match maps::read() {
+        Ok(maps) => describe_in(&maps, address),
+        Err(_) => format!("{address:#x}"),
+    }
+}
+
+/// The C view of a `Module`. The strings stay valid until the next `module_count` call.
+#[repr(C)]
+#[derive(Debug)]
+pub struct ModuleInfo {
+    pub base: usize,
+    pub size: usize,
+    /// bit 0 read, bit 1 write, bit 2 execute, over all segments
+    pub perms: u8,
+    pub name: *const c_char,
+    pub path: *const c_char,
+}
+
+struct SnapshotEntry {
+    module: Module,
+    c_name: CString,
+    c_path: CString,
+}
+
+lazy_static! {
+    /// what `module_count` last saw, so indices stay stable between calls
+    static ref SNAPSHOT: RwLock<Vec<SnapshotEntry>> = RwLock::new(Vec::new());
+}
+
+impl SnapshotEntry {
+    fn info(&self) -> ModuleInfo {
+        let perms = self.module.permissions();
+
+        ModuleInfo {
+            base: self.module.base,
+            size: self.module.size(),
+            perms: perms.read as u8 | (perms.write as u8) << 1 | (perms.execute as u8) << 2,
+            name: self.c_name.as_ptr(),
+            path: self.c_path.as_ptr(),
+        }
+    }
+}
+
+/// Takes a fresh snapshot of the loaded modules and returns how many there are.
+pub extern "C" fn module_count() -> u32 {
+    let modules = all().unwrap_or_default();
+
+    let Ok(mut snapshot) = SNAPSHOT.write() else {
+        return 0;
+    };
+
+    *snapshot = modules
+        .into_iter()
+        .map(|module| SnapshotEntry {
+            c_name: CString::new(module.name.as_str()).unwrap_or_default(),
+            c_path: CString::new(module.path.as_str()).unwrap_or_default(),
+            module,
+        })
+        .collect();
+
+    snapshot.len() as u32
+}
+
+/// Fills `out` with the module at `index` of the last snapshot.
+///
+/// # Safety
+/// `out` must be null or point to a writable `ModuleInfo`.
+pub unsafe extern "C" fn module_get(index: u32, out: *mut ModuleInfo) -> bool {
+    if out.is_null() {
+        return false;
+    }
+
+    let Ok(snapshot) = SNAPSHOT.read() else {
+        return false;
+    };
+
+    match snapshot.get(index as usize) {
+        Some(entry) => {
+            *out = entry.info();
+            true
+        }
+        None => false,
+    }
+}
+
+/// Resolves `address` to a module of the last snapshot, writing its index and the offset into it.
+///
+/// # Safety
+/// `index` and `offset` must be null or point to writable values.
+pub unsafe extern "C" fn module_resolve(address: usize, index: *mut u32, offset: *mut usize) -> bool {
+    let Ok(snapshot) = SNAPSHOT.read() else {
+        return false;
+    };
+
+    let Some((i, entry)) = snapshot.iter().enumerate().find(|(_, e)| e.module.contains(address)) else {
+        return false;
+    };
+
+    if !index.is_null() {
+        *index = i as u32;
+    }
+
+    if !offset.is_null() {
+        *offset = address - entry.module.base;
+    }
+
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const APK: &str = "/data/app/com.example.game/base.apk";
+
+    fn elf_segment() -> Vec<u8> {
+        let mut memory = vec![0u8; 64];
+        memory[..elf::ELF_MAGIC.len()].copy_from_slice(&elf::ELF_MAGIC);
+        memory
+    }
+
+    fn segment() -> Vec<u8> {
+        vec![0u8; 64]
+    }
+
+    fn entry(memory: &[u8], read: bool, offset: usize, path: &str) -> MapEntry {
+        MapEntry {
+            start: memory.as_ptr() as usize,
+            end: memory.as_ptr() as usize + memory.len(),
+            perms: Permissions { read, execute: true, private: true, ..Default::default() },
+            offset,
+            path: path.to_string(),
+        }
+    }
+
+    #[test]
+    fn groups_segments_by_file() {
+        let (foo_header, foo_code, bar_header) = (elf_segment(), segment(), elf_segment());
+        let maps = [
+            entry(&foo_header, true, 0, "/usr/lib/libfoo.so"),
+            entry(&foo_code, true, 0x1000, "/usr/lib/libfoo.so"),
+            entry(&segment(), true, 0, ""),
+            entry(&segment(), true, 0, "[stack]"),
+            entry(&bar_header, true, 0, "/usr/lib/libbar.so"),
+        ];
+
+        let modules = group(&maps);
+
+        assert_eq!(modules.len(), 2);
+        assert_eq!(modules[0].name, "libfoo.so");
+        assert_eq!(modules[0].segments.len(), 2);
+        assert_eq!(modules[0].base, maps[0].start.min(maps[1].start));
+        assert_eq!(modules[0].end, maps[0].end.max(maps[1].end));
+        assert_eq!(modules[1].name, "libbar.so");
+        assert_eq!(modules[1].segments.len(), 1);
+    }
+
+    #[test]
+    fn splits_libraries_embedded_in_one_apk() {
+        let (first_header, first_code) = (elf_segment(), segment());
+        let (second_header, second_code) = (elf_segment(), segment());
+        let maps = [
+            entry(&first_header, true, 0x1000, APK),
+            entry(&first_code, true, 0x2000, APK),
+            entry(&second_header, true, 0x9000, APK),
+            entry(&second_code, true, 0xa000, APK),
+        ];
+
+        let modules = group(&maps);
+
+        assert_eq!(modules.len(), 2);
+        assert!(modules.iter().all(|m| m.path == APK && m.segments.len() == 2));
+        assert_eq!(modules[0].base, maps[0].start.min(maps[1].start));
+        assert_eq!(modules[1].base, maps[2].start.min(maps[3].start));
+    }
+
+    #[test]
+    fn splits_when_the_offset_goes_back() {
+        // not readable, so only the offsets can tell them apart
+        let maps = [
+            entry(&segment(), false, 0x5000, APK),
+            entry(&segment(), false, 0x6000, APK),
+            entry(&segment(), false, 0x1000, APK),
+        ];
+
+        let modules = group(&maps);
+
+        assert_eq!(modules.len(), 2);
+        assert_eq!(modules[0].segments.len(), 2);
+        assert_eq!(modules[1].segments[0].offset, 0x1000);
+    }
+}
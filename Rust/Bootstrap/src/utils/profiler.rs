@@ -0,0 +1,102 @@
+//! Measures how long each part of startup takes.
+//!
+//! Once MelonLoader has started, or startup failed, the timings are written to
+//! `MelonLoader/Bootstrap-Timings.json`, right next to `Latest-Bootstrap.log`, and summarized in the log.
+
+use std::{
+    path::PathBuf,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use lazy_static::lazy_static;
+use serde::Serialize;
+
+use crate::{constants::MELON_VERSION, errors::DynErr, log, melonenv::paths, phase};
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Timing {
+    pub name: String,
+    /// milliseconds since the first measured stage started
+    pub offset_ms: f64,
+    pub duration_ms: f64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TimingReport<'a> {
+    version: &'a str,
+    total_ms: f64,
+    /// the phase startup failed to reach, if it failed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    failed_phase: Option<String>,
+    timings: &'a [Timing],
+}
+
+lazy_static! {
+    static ref EPOCH: Instant = Instant::now();
+    static ref TIMINGS: Mutex<Vec<Timing>> = Mutex::new(Vec::new());
+}
+
+/// Starts the clock every offset is measured from, so it doesn't depend on whichever stage is measured first.
+pub fn start() {
+    lazy_static::initialize(&EPOCH);
+}
+
+/// Runs `f`, recording how long it took under `name`.
+pub fn measure<T>(name: &str, f: impl FnOnce() -> T) -> T {
+    let start = Instant::now();
+
+    let result = f();
+
+    record(name, start, start.elapsed());
+    result
+}
+
+fn record(name: &str, start: Instant, duration: Duration) {
+    let Ok(mut timings) = TIMINGS.lock() else { return };
+
+    timings.push(Timing {
+        name: name.to_string(),
+        offset_ms: as_ms(start.saturating_duration_since(*EPOCH)),
+        duration_ms: as_ms(duration),
+    });
+}
+
+pub fn timings() -> Vec<Timing> {
+    TIMINGS.lock().map(|timings| timings.clone()).unwrap_or_default()
+}
+
+pub fn report_path() -> PathBuf {
+    paths::MELONLOADER_FOLDER.join("Bootstrap-Timings.json")
+}
+
+/// Writes the timing report, and logs a one line summary of it.
+pub fn write_report() -> Result<(), DynErr> {
+    let timings = timings();
+    let total_ms = as_ms(EPOCH.elapsed());
+
+    let report = TimingReport {
+        version: MELON_VERSION,
+        total_ms,
+        failed_phase: phase::failure().map(|f| format!("{:?}", f.phase)),
+        timings: &timings,
+    };
+
+    std::fs::write(report_path(), serde_json::to_string_pretty(&report)?)?;
+
+    let summary = timings
+        .iter()
+        .map(|t| format!("{} {:.0}ms", t.name, t.duration_ms))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    log!("Startup timings: {} (total {:.0}ms)", summary, total_ms);
+
+    Ok(())
+}
+
+fn as_ms(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
@@ -2,7 +2,7 @@ use std::{error::Error, collections::HashMap, io, path::Path};
 
 use unity_rs::{runtime::FerrexRuntime, mono::Mono};
 
-use crate::{errors::DynErr, melonenv::paths};
+use crate::{errors::DynErr, melonenv::paths, utils::dotnet_versions};
 
 #[allow(dead_code)]
 pub static mut RUNTIME: Option<FerrexRuntime> = None;
@@ -37,7 +37,7 @@ pub fn get_mono_library() -> Result<&'static Mono, DynErr> {
     unsafe {
         if MONO_LIB.is_none() {
             // mono is statically linked for linux-bionic's coreclr
-            let dotnet_path = paths::get_dotnet_path()?.join("shared/Microsoft.NETCore.App/8.0.6/libcoreclr.so");
+            let dotnet_path = dotnet_versions::framework_dir()?.join("libcoreclr.so");
             MONO_LIB = Some(unity_rs::mono::Mono::new(dotnet_path)?)
         }
 
@@ -0,0 +1,293 @@
+//! Parsing and validation of `MelonLoader.runtimeconfig.json`.
+//!
+//! hostfxr only reports a generic failure code when the file is off, so everything it would
+//! trip over is checked here first and turned into a `DotnetErr` that says what is wrong.
+
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+};
+
+use serde::Deserialize;
+
+use crate::{
+    debug, debug_enabled,
+    errors::{dotneterr::DotnetErr, DynErr},
+    melonenv::paths,
+    utils::dotnet_versions::{DotnetVersion, RollForward},
+};
+
+pub const RUNTIME_CONFIG_NAME: &str = "MelonLoader.runtimeconfig.json";
+
+/// The framework that ships the runtime itself, everything else layers on top of it.
+pub const NETCORE_APP: &str = "Microsoft.NETCore.App";
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RuntimeConfig {
+    pub runtime_options: RuntimeOptions,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RuntimeOptions {
+    pub tfm: Option<String>,
+    pub framework: Option<FrameworkReference>,
+    pub frameworks: Option<Vec<FrameworkReference>>,
+    pub included_frameworks: Option<Vec<FrameworkReference>>,
+    pub roll_forward: Option<String>,
+    pub roll_forward_on_no_candidate_fx: Option<u32>,
+    pub apply_patches: Option<bool>,
+    pub config_properties: Option<serde_json::Map<String, serde_json::Value>>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FrameworkReference {
+    pub name: String,
+    pub version: String,
+    pub roll_forward: Option<String>,
+}
+
+impl RuntimeConfig {
+    pub fn load(path: &Path) -> Result<Self, DotnetErr> {
+        if !path.exists() {
+            return Err(DotnetErr::RuntimeConfig);
+        }
+
+        let contents = std::fs::read_to_string(path).map_err(DotnetErr::UnreadableRuntimeConfig)?;
+        Self::parse(&contents)
+    }
+
+    /// Parses and validates the contents of a runtime config.
+    pub fn parse(contents: &str) -> Result<Self, DotnetErr> {
+        let config: Self = serde_json::from_str(contents)?;
+        config.validate()?;
+
+        Ok(config)
+    }
+
+    /// Every framework the config references, from either `framework` or `frameworks`.
+    pub fn frameworks(&self) -> Vec<&FrameworkReference> {
+        let options = &self.runtime_options;
+
+        options
+            .framework
+            .iter()
+            .chain(options.frameworks.iter().flatten())
+            .collect()
+    }
+
+    /// The framework the runtime gets loaded from.
+    pub fn runtime_framework(&self) -> Result<&FrameworkReference, DotnetErr> {
+        let frameworks = self.frameworks();
+
+        frameworks
+            .iter()
+            .find(|f| f.name == NETCORE_APP)
+            .or(frameworks.first())
+            .copied()
+            .ok_or(DotnetErr::MissingFramework)
+    }
+
+    /// A policy on the framework reference wins over the global one, which wins over the legacy settings.
+    pub fn roll_forward(&self, framework: &FrameworkReference) -> Result<RollForward, DotnetErr> {
+        let options = &self.runtime_options;
+
+        if let Some(value) = framework.roll_forward.as_ref().or(options.roll_forward.as_ref()) {
+            return RollForward::parse(value).ok_or_else(|| DotnetErr::UnknownRollForward(value.clone()));
+        }
+
+        Ok(match options.roll_forward_on_no_candidate_fx {
+            Some(0) if options.apply_patches == Some(false) => RollForward::Disable,
+            Some(0) => RollForward::LatestPatch,
+            Some(2) => RollForward::Major,
+            _ => RollForward::default(),
+        })
+    }
+
+    fn validate(&self) -> Result<(), DotnetErr> {
+        let options = &self.runtime_options;
+
+        if options.framework.is_some() && options.frameworks.is_some() {
+            return Err(DotnetErr::ConflictingRuntimeConfig(
+                "'framework' and 'frameworks' can't both be set".to_string(),
+            ));
+        }
+
+        // includedFrameworks is written for self-contained apps, which never go through a dotnet root
+        if options.included_frameworks.is_some() {
+            return Err(DotnetErr::ConflictingRuntimeConfig(
+                "'includedFrameworks' is only valid for self-contained apps".to_string(),
+            ));
+        }
+
+        if options.roll_forward.is_some() && options.roll_forward_on_no_candidate_fx.is_some() {
+            return Err(DotnetErr::ConflictingRuntimeConfig(
+                "'rollForward' can't be combined with 'rollForwardOnNoCandidateFx'".to_string(),
+            ));
+        }
+
+        if let Some(value) = options.roll_forward_on_no_candidate_fx {
+            if value > 2 {
+                return Err(DotnetErr::ConflictingRuntimeConfig(format!(
+                    "'rollForwardOnNoCandidateFx' must be 0, 1 or 2, got {value}"
+                )));
+            }
+        }
+
+        let frameworks = self.frameworks();
+        if frameworks.is_empty() {
+            return Err(DotnetErr::MissingFramework);
+        }
+
+        let mut seen = HashSet::new();
+        for framework in frameworks {
+            if !seen.insert(framework.name.as_str()) {
+                return Err(DotnetErr::ConflictingRuntimeConfig(format!(
+                    "framework '{}' is referenced more than once",
+                    framework.name
+                )));
+            }
+
+            if DotnetVersion::parse(&framework.version).is_none() {
+                return Err(DotnetErr::InvalidFrameworkVersion {
+                    name: framework.name.clone(),
+                    version: framework.version.clone(),
+                });
+            }
+
+            self.roll_forward(framework)?;
+        }
+
+        Ok(())
+    }
+
+    /// Logs the configuration hostfxr is about to be initialized with.
+    pub fn dump(&self, path: &Path) -> Result<(), DynErr> {
+        if !debug_enabled!() {
+            return Ok(());
+        }
+
+        let options = &self.runtime_options;
+
+        debug!("[Dotnet] Runtime config: {}", path.display())?;
+        debug!("[Dotnet]   tfm: {}", options.tfm.as_deref().unwrap_or("<none>"))?;
+
+        for framework in self.frameworks() {
+            debug!(
+                "[Dotnet]   framework: {} {} (roll forward: {})",
+                framework.name,
+                framework.version,
+                self.roll_forward(framework)?
+            )?;
+        }
+
+        for (key, value) in options.config_properties.iter().flatten() {
+            debug!("[Dotnet]   {} = {}", key, value)?;
+        }
+
+        Ok(())
+    }
+}
+
+pub fn path() -> Result<PathBuf, DynErr> {
+    Ok(paths::runtime_dir()?.join(RUNTIME_CONFIG_NAME))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const VALID: &str = r#"{
+        "runtimeOptions": {
+            "tfm": "net6.0",
+            "framework": { "name": "Microsoft.NETCore.App", "version": "6.0.0" },
+            "rollForward": "LatestMinor"
+        }
+    }"#;
+
+    #[test]
+    fn accepts_a_valid_config() {
+        let config = RuntimeConfig::parse(VALID).unwrap();
+        let framework = config.runtime_framework().unwrap();
+
+        assert_eq!(framework.name, NETCORE_APP);
+        assert_eq!(config.roll_forward(framework).unwrap(), RollForward::LatestMinor);
+    }
+
+    #[test]
+    fn rejects_a_missing_framework() {
+        let result = RuntimeConfig::parse(r#"{ "runtimeOptions": { "tfm": "net6.0" } }"#);
+        assert!(matches!(result, Err(DotnetErr::MissingFramework)));
+
+        let result = RuntimeConfig::parse(r#"{ "runtimeOptions": { "frameworks": [] } }"#);
+        assert!(matches!(result, Err(DotnetErr::MissingFramework)));
+    }
+
+    #[test]
+    fn rejects_an_unknown_roll_forward() {
+        let global = VALID.replace("LatestMinor", "Sideways");
+        match RuntimeConfig::parse(&global) {
+            Err(DotnetErr::UnknownRollForward(value)) => assert_eq!(value, "Sideways"),
+            other => panic!("expected UnknownRollForward, got {other:?}"),
+        }
+
+        let per_framework = r#"{
+            "runtimeOptions": {
+                "framework": { "name": "Microsoft.NETCore.App", "version": "6.0.0", "rollForward": "Nope" }
+            }
+        }"#;
+        match RuntimeConfig::parse(per_framework) {
+            Err(DotnetErr::UnknownRollForward(value)) => assert_eq!(value, "Nope"),
+            other => panic!("expected UnknownRollForward, got {other:?}"),
+        }
+
+        let legacy = r#"{
+            "runtimeOptions": {
+                "framework": { "name": "Microsoft.NETCore.App", "version": "6.0.0" },
+                "rollForwardOnNoCandidateFx": 3
+            }
+        }"#;
+        assert!(matches!(
+            RuntimeConfig::parse(legacy),
+            Err(DotnetErr::ConflictingRuntimeConfig(_))
+        ));
+    }
+
+    #[test]
+    fn rejects_malformed_json() {
+        let result = RuntimeConfig::parse(r#"{ "runtimeOptions": { "framework": "#);
+        assert!(matches!(result, Err(DotnetErr::MalformedRuntimeConfig(_))));
+
+        // well formed, but missing runtimeOptions entirely
+        let result = RuntimeConfig::parse("{}");
+        assert!(matches!(result, Err(DotnetErr::MalformedRuntimeConfig(_))));
+
+        let result = RuntimeConfig::parse(r#"{ "runtimeOptions": { "framework": { "name": 6 } } }"#);
+        assert!(matches!(result, Err(DotnetErr::MalformedRuntimeConfig(_))));
+    }
+
+    #[test]
+    fn rejects_conflicting_properties() {
+        let both = r#"{
+            "runtimeOptions": {
+                "framework": { "name": "Microsoft.NETCore.App", "version": "6.0.0" },
+                "frameworks": [{ "name": "Microsoft.AspNetCore.App", "version": "6.0.0" }]
+            }
+        }"#;
+        assert!(matches!(
+            RuntimeConfig::parse(both),
+            Err(DotnetErr::ConflictingRuntimeConfig(_))
+        ));
+
+        let bad_version = VALID.replace("\"6.0.0\"", "\"six\"");
+        match RuntimeConfig::parse(&bad_version) {
+            Err(DotnetErr::InvalidFrameworkVersion { name, version }) => {
+                assert_eq!(name, NETCORE_APP);
+                assert_eq!(version, "six");
+            }
+            other => panic!("expected InvalidFrameworkVersion, got {other:?}"),
+        }
+    }
+}
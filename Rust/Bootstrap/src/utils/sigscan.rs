@@ -0,0 +1,139 @@
+//! IDA style signature scanning, for finding functions that aren't exported.
+//!
+//! Patterns are space separated hex bytes, with `?` or `??` matching any byte:
+//! `48 8B 05 ?? ?? ?? ?? 48 85 C0`.
+
+use std::ffi::{c_char, CStr};
+
+use crate::{
+    debug,
+    errors::{sigscanerr::SigScanError, DynErr},
+    utils::modules,
+};
+
+#[derive(Debug, Clone)]
+pub struct Pattern {
+    bytes: Vec<Option<u8>>,
+    /// index of the first fixed byte, used to skip ahead quickly
+    anchor: usize,
+}
+
+impl Pattern {
+    pub fn parse(pattern: &str) -> Result<Self, SigScanError> {
+        let bytes = pattern
+            .split_whitespace()
+            .map(|b| match b {
+                "?" | "??" => Ok(None),
+                _ if b.len() == 2 => u8::from_str_radix(b, 16)
+                    .map(Some)
+                    .map_err(|_| SigScanError::InvalidByte(b.to_string())),
+                _ => Err(SigScanError::InvalidByte(b.to_string())),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if bytes.is_empty() {
+            return Err(SigScanError::EmptyPattern);
+        }
+
+        let anchor = bytes
+            .iter()
+            .position(Option::is_some)
+            .ok_or(SigScanError::OnlyWildcards)?;
+
+        Ok(Self { bytes, anchor })
+    }
+
+    pub fn len(&self) -> usize {
+        self.bytes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    fn matches(&self, window: &[u8]) -> bool {
+        self.bytes
+            .iter()
+            .zip(window)
+            .all(|(p, b)| p.is_none_or(|p| p == *b))
+    }
+
+    /// Every offset in `haystack` where the pattern matches.
+    pub fn find_all(&self, haystack: &[u8]) -> Vec<usize> {
+        let mut matches = Vec::new();
+        if haystack.len() < self.len() {
+            return matches;
+        }
+
+        let anchor_byte = self.bytes[self.anchor].unwrap_or_default();
+        let last_start = haystack.len() - self.len();
+
+        let mut position = self.anchor;
+        while let Some(found) = haystack[position..=last_start + self.anchor]
+            .iter()
+            .position(|b| *b == anchor_byte)
+        {
+            let start = position + found - self.anchor;
+            if self.matches(&haystack[start..]) {
+                matches.push(start);
+            }
+
+            position += found + 1;
+            if position > last_start + self.anchor {
+                break;
+            }
+        }
+
+        matches
+    }
+}
+
+/// Scans every executable segment of `module` (a file name like `libunity.so`), returning absolute addresses.
+pub fn scan_module(module: &str, pattern: &str) -> Result<Vec<usize>, DynErr> {
+    let pattern = Pattern::parse(pattern)?;
+
+    let Some(found) = modules::find(module)? else {
+        return Err(SigScanError::ModuleNotFound(module.to_string()).into());
+    };
+
+    let mut matches = Vec::new();
+    for segment in found.executable_segments() {
+        let memory = unsafe { std::slice::from_raw_parts(segment.start as *const u8, segment.size()) };
+
+        matches.extend(pattern.find_all(memory).into_iter().map(|offset| segment.start + offset));
+    }
+
+    debug!("Signature scan of {} found {} match(es)", module, matches.len())?;
+
+    Ok(matches)
+}
+
+/// Scans `module` for `pattern`, writing up to `max` addresses into `out`.
+/// Returns the total number of matches, which can be more than what fit into `out`.
+///
+/// # Safety
+/// `module` and `pattern` must be valid C strings, and `out` must have room for `max` addresses.
+pub unsafe extern "C" fn sig_scan(module: *const c_char, pattern: *const c_char, out: *mut usize, max: u32) -> u32 {
+    if module.is_null() || pattern.is_null() {
+        return 0;
+    }
+
+    let module = CStr::from_ptr(module).to_string_lossy();
+    let pattern = CStr::from_ptr(pattern).to_string_lossy();
+
+    let matches = match scan_module(&module, &pattern) {
+        Ok(matches) => matches,
+        Err(e) => {
+            let _ = crate::error!("Signature scan of {} failed: {}", module, e.to_string());
+            return 0;
+        }
+    };
+
+    if !out.is_null() {
+        for (i, address) in matches.iter().take(max as usize).enumerate() {
+            *out.add(i) = *address;
+        }
+    }
+
+    matches.len() as u32
+}
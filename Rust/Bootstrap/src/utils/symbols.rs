@@ -0,0 +1,290 @@
+//! Resolves symbols from the ELF files behind loaded modules, including `.symtab` entries the dynamic linker never sees.
+//!
+//! Besides the module's own file, symbols are read from sidecar files in `MelonLoader/Dependencies/Symbols`:
+//! `<module>.sym`, `<module>.debug`, or Unity's `<name>.sym.so`. Sidecars can be ELF files, or text with one
+//! `<hex address> [type] <name>` per line, which is what `nm` prints. Lines without an address are skipped.
+//!
+//! A module's table is only read once, even if nothing could be read, so failed lookups stay cheap.
+
+use std::{
+    collections::HashMap,
+    ffi::{c_char, c_void, CStr},
+    fs::File,
+    mem::size_of,
+    os::unix::fs::FileExt,
+    path::{Path, PathBuf},
+    ptr::null_mut,
+    sync::{Arc, Mutex},
+};
+
+use lazy_static::lazy_static;
+
+use crate::{
+    debug,
+    errors::{symbolerr::SymbolError, DynErr},
+    warn,
+    melonenv::paths,
+    utils::{
+        elf::{self, Ehdr, Phdr, Shdr, Sym},
+        modules::{self, Module},
+    },
+};
+
+const STT_SECTION: u8 = 3;
+const STT_FILE: u8 = 4;
+
+/// Every symbol found for one module, by the address the file says it has.
+#[derive(Debug, Default)]
+pub struct SymbolTable {
+    /// the lowest `PT_LOAD` address, what the module's base in memory corresponds to
+    pub load_base: usize,
+    pub symbols: HashMap<String, usize>,
+}
+
+impl SymbolTable {
+    /// The runtime address of `name`, given where the module starts in memory.
+    pub fn address(&self, module: &Module, name: &str) -> Option<usize> {
+        let page_size = unsafe { libc::sysconf(libc::_SC_PAGESIZE) } as usize;
+        let value = *self.symbols.get(name)?;
+
+        Some(module.base - (self.load_base & !(page_size - 1)) + value)
+    }
+}
+
+lazy_static! {
+    /// keyed by the module's path and where its image starts in that file, libraries in an apk share the path
+    static ref CACHE: Mutex<HashMap<(String, u64), Arc<SymbolTable>>> = Mutex::new(HashMap::new());
+}
+
+/// Resolves `symbol` in `module` (a file name like `libunity.so`) to an address in memory.
+pub fn resolve(module: &str, symbol: &str) -> Result<usize, DynErr> {
+    let found = modules::find(module)?.ok_or_else(|| SymbolError::ModuleNotFound(module.to_string()))?;
+
+    table(&found)?
+        .address(&found, symbol)
+        .ok_or_else(|| {
+            SymbolError::SymbolNotFound {
+                module: module.to_string(),
+                symbol: symbol.to_string(),
+            }
+            .into()
+        })
+}
+
+/// The symbol table of `module`, read from disk the first time it's asked for.
+pub fn table(module: &Module) -> Result<Arc<SymbolTable>, DynErr> {
+    let mut cache = CACHE.lock().map_err(|_| SymbolError::Malformed(module.path.clone()))?;
+
+    // libraries loaded straight out of an apk are mapped from an offset into it
+    let origin = module.segments.iter().min_by_key(|s| s.start).map_or(0, |s| s.offset) as u64;
+    let key = (module.path.clone(), origin);
+
+    if let Some(table) = cache.get(&key) {
+        return Ok(table.clone());
+    }
+
+    let table = Arc::new(load(module, origin));
+    cache.insert(key, table.clone());
+
+    Ok(table)
+}
+
+/// Reads everything there is for `module`. Unreadable files are logged and skipped, whatever was found is kept.
+fn load(module: &Module, origin: u64) -> SymbolTable {
+    let mut table = SymbolTable::default();
+
+    let own = ElfFile::open(Path::new(&module.path), origin).and_then(|elf| {
+        table.load_base = elf.load_base()?;
+        elf.read_symbols(&mut table.symbols)
+    });
+
+    if let Err(e) = own {
+        let _ = debug!("Could not read symbols from {}: {}", module.path, e.to_string());
+    }
+
+    for sidecar in sidecars(&module.name).into_iter().filter(|p| p.is_file()) {
+        let before = table.symbols.len();
+
+        match read_sidecar(&sidecar, &mut table.symbols) {
+            Ok(_) => {
+                let _ = debug!("Loaded {} symbol(s) from {}", table.symbols.len() - before, sidecar.display());
+            }
+            Err(e) => {
+                let _ = warn!("Could not read symbols from {}: {}", sidecar.display(), e.to_string());
+            }
+        }
+    }
+
+    let _ = debug!("{} has {} symbol(s)", module.name, table.symbols.len());
+
+    table
+}
+
+fn sidecars(name: &str) -> Vec<PathBuf> {
+    let dir = &paths::SYMBOLS_FOLDER;
+    let stem = name.strip_suffix(".so").unwrap_or(name);
+
+    vec![
+        dir.join(format!("{name}.sym")),
+        dir.join(format!("{name}.debug")),
+        dir.join(format!("{stem}.sym.so")),
+    ]
+}
+
+fn read_sidecar(path: &Path, symbols: &mut HashMap<String, usize>) -> Result<(), DynErr> {
+    if let Ok(elf) = ElfFile::open(path, 0) {
+        return elf.read_symbols(symbols);
+    }
+
+    for line in std::fs::read_to_string(path)?.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        let [address, .., name] = fields.as_slice() else {
+            continue;
+        };
+
+        // undefined symbols have no address, `nm` prints `U name` for them
+        let address = address.trim_start_matches("0x");
+        let Ok(address) = usize::from_str_radix(address, 16) else {
+            continue;
+        };
+
+        symbols.entry(name.to_string()).or_insert(address);
+    }
+
+    Ok(())
+}
+
+/// An ELF image inside a file, starting at `origin`.
+struct ElfFile {
+    file: File,
+    origin: u64,
+    /// what's left of the file from `origin`, nothing is read past it
+    len: u64,
+    name: String,
+    header: Ehdr,
+}
+
+// the header fields are narrower on 32 bit, so the casts aren't redundant there
+#[allow(clippy::unnecessary_cast)]
+impl ElfFile {
+    fn open(path: &Path, origin: u64) -> Result<Self, DynErr> {
+        let file = File::open(path)?;
+        let len = file.metadata()?.len().saturating_sub(origin);
+
+        let mut elf = ElfFile {
+            file,
+            origin,
+            len,
+            name: path.display().to_string(),
+            header: unsafe { std::mem::zeroed() },
+        };
+
+        let ident: [u8; 16] = elf.read(0)?;
+        if ident[..4] != elf::ELF_MAGIC || ident[elf::EI_CLASS] != elf::ELF_CLASS {
+            return Err(SymbolError::NotElf(elf.name).into());
+        }
+
+        elf.header = elf.read(0)?;
+
+        Ok(elf)
+    }
+
+    fn read_bytes(&self, offset: u64, len: usize) -> Result<Vec<u8>, SymbolError> {
+        // sizes come straight from the file, a corrupt one shouldn't turn into a huge allocation
+        if offset.checked_add(len as u64).is_none_or(|end| end > self.len) {
+            return Err(SymbolError::Malformed(self.name.clone()));
+        }
+
+        let mut buffer = vec![0u8; len];
+        self.file
+            .read_exact_at(&mut buffer, self.origin + offset)
+            .map_err(|_| SymbolError::Malformed(self.name.clone()))?;
+
+        Ok(buffer)
+    }
+
+    fn read<T: Copy>(&self, offset: u64) -> Result<T, SymbolError> {
+        let bytes = self.read_bytes(offset, size_of::<T>())?;
+        Ok(unsafe { std::ptr::read_unaligned(bytes.as_ptr() as *const T) })
+    }
+
+    fn load_base(&self) -> Result<usize, DynErr> {
+        let mut base: Option<usize> = None;
+
+        for i in 0..self.header.e_phnum as u64 {
+            let phdr: Phdr = self.read(self.header.e_phoff as u64 + i * self.header.e_phentsize as u64)?;
+            if phdr.p_type == elf::PT_LOAD {
+                let vaddr = phdr.p_vaddr as usize;
+                base = Some(base.map_or(vaddr, |b| b.min(vaddr)));
+            }
+        }
+
+        Ok(base.unwrap_or_default())
+    }
+
+    /// Adds every defined symbol from `.symtab` and `.dynsym`, keeping what's already in `symbols`.
+    fn read_symbols(&self, symbols: &mut HashMap<String, usize>) -> Result<(), DynErr> {
+        let sections: Vec<Shdr> = (0..self.header.e_shnum as u64)
+            .map(|i| self.read(self.header.e_shoff as u64 + i * self.header.e_shentsize as u64))
+            .collect::<Result<_, _>>()?;
+
+        for section in sections
+            .iter()
+            .filter(|s| s.sh_type == elf::SHT_SYMTAB || s.sh_type == elf::SHT_DYNSYM)
+        {
+            let strings = sections
+                .get(section.sh_link as usize)
+                .ok_or_else(|| SymbolError::Malformed(self.name.clone()))?;
+            let strings = self.read_bytes(strings.sh_offset as u64, strings.sh_size as usize)?;
+            let table = self.read_bytes(section.sh_offset as u64, section.sh_size as usize)?;
+
+            for entry in table.chunks_exact(size_of::<Sym>()) {
+                let sym = unsafe { std::ptr::read_unaligned(entry.as_ptr() as *const Sym) };
+                let kind = sym.st_info & 0xF;
+
+                if sym.st_shndx == elf::SHN_UNDEF || sym.st_value == 0 || kind == STT_SECTION || kind == STT_FILE {
+                    continue;
+                }
+
+                let Some(name) = strings
+                    .get(sym.st_name as usize..)
+                    .and_then(|s| CStr::from_bytes_until_nul(s).ok())
+                    .and_then(|s| s.to_str().ok())
+                else {
+                    continue;
+                };
+
+                if !name.is_empty() {
+                    symbols.entry(name.to_string()).or_insert(sym.st_value as usize);
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Resolves `symbol` in `module`, returning null if it can't be found.
+///
+/// # Safety
+/// `module` and `symbol` must be valid C strings.
+pub unsafe extern "C" fn symbol_resolve(module: *const c_char, symbol: *const c_char) -> *mut c_void {
+    if module.is_null() || symbol.is_null() {
+        return null_mut();
+    }
+
+    let module = CStr::from_ptr(module).to_string_lossy();
+    let symbol = CStr::from_ptr(symbol).to_string_lossy();
+
+    match resolve(&module, &symbol) {
+        Ok(address) => address as *mut c_void,
+        Err(e) => {
+            let _ = crate::error!("Failed to resolve {} in {}: {}", symbol, module, e.to_string());
+            null_mut()
+        }
+    }
+}
@@ -0,0 +1,36 @@
+//! Regenerates `Bootstrap/include/melonloader.h` from the Bootstrap's C ABI.
+//!
+//! Run with `cargo run --manifest-path Rust/HeaderGen/Cargo.toml` after changing `src/abi.rs`, and commit the result.
+//! CI runs it too, and fails if the committed header is out of date.
+
+use std::{path::PathBuf, process::ExitCode};
+
+fn main() -> ExitCode {
+    let bootstrap_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("..").join("Bootstrap");
+
+    let config = match cbindgen::Config::from_file(bootstrap_dir.join("cbindgen.toml")) {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("Failed to read cbindgen.toml: {e}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    // only the ABI itself, and phase.rs for LoaderPhase, so nothing else in the crate leaks into the header
+    let bindings = cbindgen::Builder::new()
+        .with_config(config)
+        .with_src(bootstrap_dir.join("src").join("abi.rs"))
+        .with_src(bootstrap_dir.join("src").join("phase.rs"))
+        .generate();
+
+    match bindings {
+        Ok(bindings) => {
+            bindings.write_to_file(bootstrap_dir.join("include").join("melonloader.h"));
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("Failed to generate include/melonloader.h: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
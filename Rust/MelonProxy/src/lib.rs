@@ -1,5 +1,4 @@
 pub mod nativeloader;
-pub mod libloading;
 
 use jni::{
     objects::{JClass, JString},
@@ -8,7 +7,7 @@ use jni::{
 };
 use std::{mem, os::raw::c_void, panic::catch_unwind, path::PathBuf};
 
-use libloading::load_lib;
+use shared::libloading::{self, load_lib};
 #[macro_use]
 extern crate log;
 
@@ -23,7 +22,7 @@ pub extern "system" fn JNI_OnLoad(vm: JavaVM, _: *mut c_void) -> jint {
     vm.attach_current_thread()
         .expect("Unable to attach current thread to the JVM");
 
-    let self_lib = load_lib(&PathBuf::from("libmain.so"))
+    let self_lib = load_lib(&PathBuf::from("libmain.so"), libc::RTLD_NOW | libc::RTLD_NOLOAD)
         .expect("Failed to load self");
 
     let load_handle: libloading::NativeMethod<fn(JNIEnv, JClass, JString) -> jboolean> =
@@ -4,10 +4,8 @@ use jni::{
     JNIEnv,
 };
 use std::path::PathBuf;
-use std::ffi::CStr;
 
-use crate::libloading;
-use crate::libloading::load_lib;
+use shared::libloading::{self, load_lib};
 
 #[no_mangle]
 fn load(env: JNIEnv, _: JClass, _: JString) -> jboolean {
@@ -19,19 +17,13 @@ fn load(env: JNIEnv, _: JClass, _: JString) -> jboolean {
 #[no_mangle]
 fn unload(_: JNIEnv, _: JClass) {
     info!("unload");
+    unload_bootstrap();
 }
 
 fn load_bootstrap(env: &JNIEnv) {
-    let bootstrap_lib = load_lib(&PathBuf::from("libBootstrap.so"))
+    let bootstrap_lib = load_lib(&PathBuf::from("libBootstrap.so"), libc::RTLD_NOW | libc::RTLD_GLOBAL)
         .unwrap_or_else(|e| {
             error!("Failed to load libBootstrap.so: {}", e.to_string());
-
-            let dl_error = unsafe { libc::dlerror() };
-            let error_message = unsafe {
-                CStr::from_ptr(dl_error)
-            };
-            let formatted_string = error_message.to_string_lossy();
-            error!("dlerror: {}", formatted_string);
             panic!();
         });
 
@@ -54,8 +46,29 @@ fn load_bootstrap(env: &JNIEnv) {
     (initialize)();
 }
 
+fn unload_bootstrap() {
+    // libBootstrap.so is already loaded at this point, this just hands back the existing handle
+    let bootstrap_lib = match load_lib(&PathBuf::from("libBootstrap.so"), libc::RTLD_NOW | libc::RTLD_NOLOAD) {
+        Ok(lib) => lib,
+        Err(e) => {
+            error!("Failed to load libBootstrap.so: {}", e.to_string());
+            return;
+        }
+    };
+
+    let shutdown: libloading::NativeMethod<fn()> = match bootstrap_lib.sym("melonloader_shutdown") {
+        Ok(shutdown) => shutdown,
+        Err(e) => {
+            error!("Failed to find melonloader_shutdown: {}", e.to_string());
+            return;
+        }
+    };
+
+    (shutdown)();
+}
+
 fn load_lib_unity(env: &JNIEnv) {
-    let unity_lib = load_lib(&PathBuf::from("libunity.so"))
+    let unity_lib = load_lib(&PathBuf::from("libunity.so"), libc::RTLD_NOW | libc::RTLD_GLOBAL)
         .expect("Couldn't load libunity!");
 
     let on_load: libloading::NativeMethod<fn(*mut JavaVM, *mut libc::c_void)> = unity_lib
@@ -0,0 +1,5 @@
+//! Code shared between the Bootstrap and `MelonProxy`.
+
+pub mod liberr;
+#[cfg(unix)]
+pub mod libloading;
@@ -0,0 +1,24 @@
+use thiserror::Error;
+
+/// possible library loading errors
+#[derive(Debug, Error)]
+pub enum LibError {
+    /// failed to load library
+    #[error("Failed to load library: {0}")]
+    FailedToLoadLib(String),
+
+    /// failed to get lib name
+    #[error("Failed to get lib name!")]
+    FailedToGetLibName,
+
+    /// failed to get lib path
+    #[error("Failed to get lib path!")]
+    FailedToGetLibPath,
+
+    /// failed to get function pointer
+    #[error("Failed to get function pointer: {0}")]
+    FailedToGetFnPtr(String),
+
+    #[error("Failed to create C-String")]
+    FailedToCreateCString,
+}
@@ -0,0 +1,118 @@
+//! utilities for permanently loading libraries, used by both the Bootstrap and `MelonProxy`
+//!
+//! Libraries loaded through here are never unloaded, unlike with the libloading crate,
+//! where they get unloaded when the library goes out of scope.
+//!
+//! # Safety
+//! this is incredibly unsafe
+
+use std::{
+    ffi::{c_int, c_void, CStr, CString},
+    marker::PhantomData,
+    ops::Deref,
+    path::{Path, PathBuf},
+};
+
+use crate::liberr::LibError;
+
+/// a representation of a permanently loaded library
+#[derive(Debug, Clone)]
+pub struct NativeLibrary {
+    /// the name of the lib
+    pub name: String,
+    /// the path to the lib
+    pub path: PathBuf,
+    /// the pointer to the lib
+    pub handle: *mut c_void,
+}
+
+unsafe impl Send for NativeLibrary {}
+unsafe impl Sync for NativeLibrary {}
+
+impl NativeLibrary {
+    /// gets a function pointer
+    pub fn sym<T>(&self, name_str: &str) -> Result<NativeMethod<T>, LibError> {
+        let display_string = name_str.to_string();
+
+        let name = CString::new(name_str).map_err(|_| LibError::FailedToCreateCString)?;
+        let ptr = unsafe { libc::dlsym(self.handle, name.as_ptr()) };
+        if ptr.is_null() {
+            return Err(LibError::FailedToGetFnPtr(display_string));
+        }
+
+        Ok(NativeMethod {
+            inner: ptr.cast(),
+            pd: PhantomData,
+        })
+    }
+}
+
+/// loads a library permanently
+///
+/// # Arguments
+///
+/// * `path` - the path to the library
+/// * `rtld` - the `dlopen` flags
+///
+/// # Errors
+///
+/// * `LibError::FailedToLoadLib` - if the library failed to load, with what `dlerror` said
+/// * `LibError::FailedToGetLibName` - if the library name failed to be retrieved
+pub fn load_lib<P: AsRef<Path>>(path: P, rtld: c_int) -> Result<NativeLibrary, LibError> {
+    let path = path.as_ref();
+
+    let path_string = path.to_str().ok_or(LibError::FailedToGetLibPath)?;
+
+    let c_path = CString::new(path_string).map_err(|_| LibError::FailedToCreateCString)?;
+
+    let lib = unsafe { libc::dlopen(c_path.as_ptr(), rtld) };
+
+    if lib.is_null() {
+        return Err(LibError::FailedToLoadLib(last_error()));
+    }
+
+    let lib_name = path
+        .file_name()
+        .ok_or(LibError::FailedToGetLibName)?
+        .to_str()
+        .ok_or(LibError::FailedToGetLibName)?
+        .to_string();
+
+    Ok(NativeLibrary {
+        name: lib_name,
+        path: path.to_path_buf(),
+        handle: lib,
+    })
+}
+
+fn last_error() -> String {
+    let error = unsafe { libc::dlerror() };
+    if error.is_null() {
+        return "unknown error".to_string();
+    }
+
+    unsafe { CStr::from_ptr(error) }.to_string_lossy().into_owned()
+}
+
+#[derive(Debug)]
+pub struct NativeMethod<T> {
+    pub inner: *mut c_void,
+    pd: PhantomData<T>,
+}
+
+unsafe impl<T: Send> Send for NativeMethod<T> {}
+unsafe impl<T: Sync> Sync for NativeMethod<T> {}
+
+impl<T> Clone for NativeMethod<T> {
+    fn clone(&self) -> NativeMethod<T> {
+        NativeMethod { ..*self }
+    }
+}
+
+impl<T> Deref for NativeMethod<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        unsafe { &*(&self.inner as *const *mut _ as *const T) }
+    }
+}